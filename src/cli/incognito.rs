@@ -0,0 +1,88 @@
+use crate::cli::flag_value;
+use crate::config::Config;
+use crate::error::AppError;
+use crate::models::IncognitoStatus;
+use crate::utils::parse_duration;
+
+use chrono::Local;
+use reqwest::blocking::{Client as HttpClient, RequestBuilder};
+use std::time::Duration;
+
+// Controls incognito mode of the running daemon through its local HTTP API:
+// `incognito on [--for 30m]`, `incognito off` or `incognito status`.
+pub fn run_incognito(args: &[String]) -> Result<(), AppError> {
+    let config = Config::load()?;
+    if !config.api.enabled {
+        return Err(AppError::ConfigError(
+            "incognito control requires the HTTP API to be enabled".to_string(),
+        ));
+    }
+
+    // A wildcard bind address isn't connectable; the daemon is local anyway
+    let host = match config.api.bind.as_str() {
+        "0.0.0.0" | "::" => "127.0.0.1",
+        bind => bind,
+    };
+    let url = format!("http://{}:{}/incognito", host, config.api.port);
+    let http_client = HttpClient::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+
+    let mut args = args.iter();
+    let request = match args.next().map(String::as_str) {
+        Some("on") => {
+            let mut request = http_client.post(&url);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--for" => {
+                        let value = flag_value(&mut args, "--for")?;
+                        if parse_duration(value).is_none() {
+                            return Err(AppError::InvalidArgument(format!(
+                                "invalid duration '{}', expected e.g. 90s, 30m or 2h",
+                                value
+                            )));
+                        }
+                        request = request.query(&[("for", value)]);
+                    }
+                    other => {
+                        return Err(AppError::InvalidArgument(format!(
+                            "unknown incognito option '{}'",
+                            other
+                        )))
+                    }
+                }
+            }
+            request
+        }
+        Some("off") => http_client.delete(&url),
+        Some("status") | None => http_client.get(&url),
+        Some(other) => {
+            return Err(AppError::InvalidArgument(format!(
+                "unknown incognito command '{}', expected on, off or status",
+                other
+            )))
+        }
+    };
+
+    print_status(&send(request)?);
+    Ok(())
+}
+
+fn send(request: RequestBuilder) -> Result<IncognitoStatus, AppError> {
+    let response = request.send()?;
+    if !response.status().is_success() {
+        return Err(AppError::HttpStatusError(response.status().as_u16()));
+    }
+    Ok(response.json()?)
+}
+
+fn print_status(status: &IncognitoStatus) {
+    match (status.active, status.until) {
+        (false, _) => println!("Incognito is off"),
+        (true, None) => println!("Incognito is on until turned off"),
+        (true, Some(until)) => println!(
+            "Incognito is on until {}",
+            until.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ),
+    }
+}
@@ -0,0 +1,20 @@
+pub mod incognito;
+pub mod rules;
+pub mod stats;
+
+// Re-exports for convenient access
+pub use incognito::run_incognito;
+pub use rules::run_rules;
+pub use stats::run_stats;
+
+use crate::error::AppError;
+
+// Pulls the value following a `--flag` argument.
+pub(crate) fn flag_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<&'a str, AppError> {
+    args.next()
+        .map(String::as_str)
+        .ok_or_else(|| AppError::InvalidArgument(format!("{} requires a value", flag)))
+}
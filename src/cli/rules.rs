@@ -0,0 +1,152 @@
+use crate::cli::flag_value;
+use crate::config::{Config, RuleActions};
+use crate::error::AppError;
+use crate::handlers::rules::RuleEngine;
+use crate::models::{ArtworkProvider, MusicProps, NowPlaying, PlaybackState};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use std::time::SystemTime;
+
+// `rules test --name <title> --artist <artist> [...]` loads and validates the
+// rules file, then shows which rules fire for the given track.
+pub fn run_rules(args: &[String]) -> Result<(), AppError> {
+    match args.first().map(String::as_str) {
+        Some("test") => run_rules_test(&args[1..]),
+        _ => Err(AppError::InvalidArgument(
+            "usage: rules test --name <title> --artist <artist> [--album <album>] \
+             [--genre <genre>] [--duration <secs>] [--position <secs>] [--state <state>] \
+             [--source itunes|musicbrainz|none] [--at 'YYYY-MM-DD HH:MM']"
+                .to_string(),
+        )),
+    }
+}
+
+fn run_rules_test(args: &[String]) -> Result<(), AppError> {
+    let config = Config::load()?;
+    let engine = RuleEngine::load(&config)?;
+
+    let mut props = MusicProps {
+        name: String::new(),
+        artist: String::new(),
+        album: String::new(),
+        genre: None,
+        duration: 0.0,
+        player_position: 0.0,
+    };
+    let mut playback_state = PlaybackState::Playing;
+    let mut artwork_provider = Some(ArtworkProvider::ITunes);
+    let mut at = Utc::now();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--name" => props.name = flag_value(&mut args, "--name")?.to_string(),
+            "--artist" => props.artist = flag_value(&mut args, "--artist")?.to_string(),
+            "--album" => props.album = flag_value(&mut args, "--album")?.to_string(),
+            "--genre" => props.genre = Some(flag_value(&mut args, "--genre")?.to_string()),
+            "--duration" => props.duration = parse_secs(flag_value(&mut args, "--duration")?)?,
+            "--position" => {
+                props.player_position = parse_secs(flag_value(&mut args, "--position")?)?
+            }
+            "--state" => {
+                let value = flag_value(&mut args, "--state")?;
+                playback_state = serde_json::from_value(value.into()).map_err(|_| {
+                    AppError::InvalidArgument(format!("unknown playback state '{}'", value))
+                })?;
+            }
+            "--source" => {
+                artwork_provider = match flag_value(&mut args, "--source")? {
+                    "itunes" => Some(ArtworkProvider::ITunes),
+                    "musicbrainz" => Some(ArtworkProvider::MusicBrainz),
+                    "none" => None,
+                    other => {
+                        return Err(AppError::InvalidArgument(format!(
+                            "unknown source '{}', expected itunes, musicbrainz or none",
+                            other
+                        )))
+                    }
+                }
+            }
+            "--at" => at = parse_local_time(flag_value(&mut args, "--at")?)?,
+            other => {
+                return Err(AppError::InvalidArgument(format!(
+                    "unknown rules test option '{}'",
+                    other
+                )))
+            }
+        }
+    }
+
+    let now_playing = NowPlaying {
+        props,
+        artwork_url: None,
+        artwork_provider,
+        playback_state,
+        position_updated_at: SystemTime::now(),
+    };
+    let outcome = engine.evaluate(&now_playing, at);
+
+    println!(
+        "{}: {} rule(s) loaded",
+        config.rules.path.display(),
+        engine.len()
+    );
+    if outcome.fired.is_empty() {
+        println!("No rules fire for this track");
+        return Ok(());
+    }
+    println!("Fired: {}", outcome.fired.join(", "));
+    print_actions(&outcome.actions);
+    Ok(())
+}
+
+fn print_actions(actions: &RuleActions) {
+    let fields = [
+        ("details", actions.details.clone()),
+        ("state", actions.state.clone()),
+        ("large_text", actions.large_text.clone()),
+        ("image", actions.image.clone()),
+        (
+            "buttons",
+            actions.buttons.as_ref().map(|buttons| {
+                buttons
+                    .iter()
+                    .map(|button| format!("{} <{}>", button.label, button.url))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+        ),
+        (
+            "activity_type",
+            actions.activity_type.map(|t| format!("{:?}", t)),
+        ),
+        (
+            "sinks",
+            actions.sinks.as_ref().map(|sinks| sinks.join(", ")),
+        ),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            println!("  {:<14} {}", name, value);
+        }
+    }
+}
+
+fn parse_secs(value: &str) -> Result<f64, AppError> {
+    value
+        .parse()
+        .map_err(|_| AppError::InvalidArgument(format!("invalid number of seconds '{}'", value)))
+}
+
+fn parse_local_time(value: &str) -> Result<DateTime<Utc>, AppError> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .ok()
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| {
+            AppError::InvalidArgument(format!(
+                "invalid time '{}', expected YYYY-MM-DD HH:MM",
+                value
+            ))
+        })
+}
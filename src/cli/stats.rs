@@ -0,0 +1,71 @@
+use crate::cli::flag_value;
+use crate::error::AppError;
+use crate::handlers::history::{load_plays, local_midnight_utc};
+use crate::handlers::stats::{build_stats_report, render_stats_report, StatsFormat};
+
+use chrono::{Days, Local, NaiveDate};
+
+const DEFAULT_RANGE_DAYS: u64 = 7;
+const DEFAULT_LIMIT: usize = 10;
+
+pub fn run_stats(args: &[String]) -> Result<(), AppError> {
+    let today = Local::now().date_naive();
+    let mut from: Option<NaiveDate> = None;
+    let mut to = today;
+    let mut format = StatsFormat::Table;
+    let mut limit = DEFAULT_LIMIT;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = Some(parse_date(flag_value(&mut args, "--from")?)?),
+            "--to" => to = parse_date(flag_value(&mut args, "--to")?)?,
+            "--format" => {
+                format = match flag_value(&mut args, "--format")? {
+                    "table" => StatsFormat::Table,
+                    "json" => StatsFormat::Json,
+                    "csv" => StatsFormat::Csv,
+                    other => {
+                        return Err(AppError::InvalidArgument(format!(
+                            "unknown format '{}', expected table, json or csv",
+                            other
+                        )))
+                    }
+                }
+            }
+            "--limit" => {
+                limit = flag_value(&mut args, "--limit")?
+                    .parse()
+                    .map_err(|_| AppError::InvalidArgument("--limit must be a number".into()))?
+            }
+            other => {
+                return Err(AppError::InvalidArgument(format!(
+                    "unknown stats option '{}'",
+                    other
+                )))
+            }
+        }
+    }
+
+    let from = from.unwrap_or_else(|| to - Days::new(DEFAULT_RANGE_DAYS - 1));
+    if from > to {
+        return Err(AppError::InvalidArgument(
+            "--from must not be after --to".to_string(),
+        ));
+    }
+
+    let plays = load_plays(
+        local_midnight_utc(from)?,
+        local_midnight_utc(to + Days::new(1))?,
+    )?;
+    let report = build_stats_report(&plays, from, to, limit);
+    print!("{}", render_stats_report(&report, format));
+
+    Ok(())
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        AppError::InvalidArgument(format!("invalid date '{}', expected YYYY-MM-DD", value))
+    })
+}
@@ -0,0 +1,15 @@
+pub mod rules;
+pub mod settings;
+
+// Re-exports for convenient access
+pub use rules::{
+    RangeCondition, RuleActions, RuleConditions, RuleDefinition, RulesFile, TextCondition,
+    TimeCondition,
+};
+pub use settings::{
+    data_dir, ApiConfig, ChatStatusConfig, Config, DiscordActivityType, DiscordButton,
+    DiscordConfig, FileOutputConfig, HistoryConfig, HookConfig, MastodonConfig, MastodonPostMode,
+    MqttConfig, PrivacyAction, PrivacyConfig, PrivacyField, PrivacyMatch, PrivacyRule,
+    ProfileConfig, RetryConfig, RulesConfig, ScheduleConfig, ScheduleRule, ScriptConfig,
+    WebhookConfig,
+};
@@ -0,0 +1,112 @@
+use crate::config::{DiscordActivityType, DiscordButton, DiscordConfig};
+use crate::models::PlaybackState;
+
+use serde::Deserialize;
+
+// Contents of the rules file: a list of `[[rule]]` tables.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RulesFile {
+    #[serde(rename = "rule")]
+    pub rules: Vec<RuleDefinition>,
+}
+
+// Every rule whose conditions all hold fires, in file order; later rules
+// override earlier actions and `stop` ends the evaluation.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub when: RuleConditions,
+    #[serde(default)]
+    pub then: RuleActions,
+    #[serde(default)]
+    pub stop: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleConditions {
+    pub name: Option<TextCondition>,
+    pub artist: Option<TextCondition>,
+    pub album: Option<TextCondition>,
+    pub genre: Option<TextCondition>,
+    pub duration: Option<RangeCondition>,
+    pub player_position: Option<RangeCondition>,
+    pub playback_state: Option<PlaybackState>,
+    // Where metadata and artwork were resolved: itunes, musicbrainz or none
+    pub source: Option<String>,
+    pub time: Option<TimeCondition>,
+}
+
+// A plain string matches exactly; `{ glob = "..." }` and `{ regex = "..." }`
+// match patterns. All comparisons are case-insensitive.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TextCondition {
+    Exact(String),
+    Glob { glob: String },
+    Regex { regex: String },
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RangeCondition {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+// Same format as schedule rules, in the schedule's time zone
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimeCondition {
+    #[serde(default)]
+    pub days: Vec<String>,
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleActions {
+    pub details: Option<String>,
+    pub state: Option<String>,
+    pub large_text: Option<String>,
+    pub image: Option<String>,
+    pub buttons: Option<Vec<DiscordButton>>,
+    pub activity_type: Option<DiscordActivityType>,
+    // Only these sinks receive the track; the others treat it as hidden
+    pub sinks: Option<Vec<String>>,
+}
+
+impl RuleActions {
+    // Layers `other` on top, keeping values it doesn't set
+    pub fn merge(&mut self, other: &RuleActions) {
+        fn layer<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+            if value.is_some() {
+                target.clone_from(value);
+            }
+        }
+        layer(&mut self.details, &other.details);
+        layer(&mut self.state, &other.state);
+        layer(&mut self.large_text, &other.large_text);
+        layer(&mut self.image, &other.image);
+        layer(&mut self.buttons, &other.buttons);
+        layer(&mut self.activity_type, &other.activity_type);
+        layer(&mut self.sinks, &other.sinks);
+    }
+
+    pub fn discord_config(&self, base: &DiscordConfig) -> DiscordConfig {
+        DiscordConfig {
+            details: self.details.clone().unwrap_or_else(|| base.details.clone()),
+            state: self.state.clone().unwrap_or_else(|| base.state.clone()),
+            large_text: self
+                .large_text
+                .clone()
+                .unwrap_or_else(|| base.large_text.clone()),
+            large_image: self.image.clone().or_else(|| base.large_image.clone()),
+            buttons: self.buttons.clone().unwrap_or_else(|| base.buttons.clone()),
+            activity_type: self.activity_type.unwrap_or(base.activity_type),
+            ..base.clone()
+        }
+    }
+}
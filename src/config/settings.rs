@@ -0,0 +1,518 @@
+use crate::error::AppError;
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_ENV: &str = "AMDRPC_CONFIG";
+const APP_DIR: &str = "apple-music-discord-rpc";
+
+// Directory for history, dead-letter logs and other generated files
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir().unwrap_or_default().join(APP_DIR)
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub api: ApiConfig,
+    pub discord: DiscordConfig,
+    pub file_output: FileOutputConfig,
+    pub history: HistoryConfig,
+    pub mqtt: MqttConfig,
+    pub webhooks: Vec<WebhookConfig>,
+    pub hooks: Vec<HookConfig>,
+    pub slack: ChatStatusConfig,
+    pub mattermost: ChatStatusConfig,
+    pub mastodon: MastodonConfig,
+    pub privacy: PrivacyConfig,
+    pub schedule: ScheduleConfig,
+    pub profiles: HashMap<String, ProfileConfig>,
+    pub rules: RulesConfig,
+    pub script: ScriptConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub bind: String,
+    pub port: u16,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        ApiConfig {
+            enabled: true,
+            bind: "127.0.0.1".to_string(),
+            port: 9763,
+        }
+    }
+}
+
+// Field templates use `{name}`, `{artist}`, `{album}`, `{duration}` and `{position}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    pub enabled: bool,
+    pub details: String,
+    pub state: String,
+    pub large_text: String,
+    // Image key or URL; defaults to the resolved artwork
+    pub large_image: Option<String>,
+    // Discord shows at most two buttons
+    pub buttons: Vec<DiscordButton>,
+    pub activity_type: DiscordActivityType,
+    pub retry: RetryConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscordActivityType {
+    Playing,
+    #[default]
+    Listening,
+    Watching,
+    Competing,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordButton {
+    pub label: String,
+    pub url: String,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        DiscordConfig {
+            enabled: true,
+            details: "{name}".to_string(),
+            state: "{artist}".to_string(),
+            large_text: "{album}".to_string(),
+            large_image: None,
+            buttons: vec![DiscordButton {
+                label: "Open Apple Music".to_string(),
+                url: "https://music.apple.com".to_string(),
+            }],
+            activity_type: DiscordActivityType::default(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FileOutputConfig {
+    pub enabled: bool,
+    pub directory: PathBuf,
+    pub template: String,
+    pub retry: RetryConfig,
+}
+
+impl Default for FileOutputConfig {
+    fn default() -> Self {
+        FileOutputConfig {
+            enabled: false,
+            directory: data_dir().join("now-playing"),
+            template: "{artist} - {name}".to_string(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    // Keep recording plays while incognito mode is on
+    pub record_incognito: bool,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            enabled: true,
+            record_incognito: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // State, attributes and availability are published below this topic
+    pub base_topic: String,
+    pub discovery: bool,
+    pub discovery_prefix: String,
+    pub device_name: String,
+    pub retry: RetryConfig,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        MqttConfig {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: "apple-music-discord-rpc".to_string(),
+            username: None,
+            password: None,
+            base_topic: "apple-music-discord-rpc".to_string(),
+            discovery: true,
+            discovery_prefix: "homeassistant".to_string(),
+            device_name: "Apple Music".to_string(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+// `body_template` is rendered with the track fields, JSON-escaped; without
+// it a default JSON payload is sent.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub name: String,
+    pub url: String,
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    pub body_template: Option<String>,
+    pub events: Vec<String>,
+    // Signs the body with HMAC-SHA256 into the `X-Signature-256` header
+    pub secret: Option<String>,
+    pub dead_letter_path: PathBuf,
+    pub retry: RetryConfig,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig {
+            name: "webhook".to_string(),
+            url: String::new(),
+            method: "POST".to_string(),
+            headers: HashMap::new(),
+            body_template: None,
+            events: ["track_changed", "paused", "resumed", "stopped"]
+                .map(String::from)
+                .to_vec(),
+            secret: None,
+            dead_letter_path: data_dir().join("webhooks-dead-letter.jsonl"),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+// A shell command run through `sh -c` for each selected event. Track fields
+// are exported as `AMDRPC_*` environment variables and the event is written
+// to stdin as JSON.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HookConfig {
+    pub name: String,
+    pub command: String,
+    pub events: Vec<String>,
+    // The command is killed once it runs longer than this
+    pub timeout_secs: u64,
+    // Events arriving while this many commands still run are skipped
+    pub max_concurrent: usize,
+}
+
+impl Default for HookConfig {
+    fn default() -> Self {
+        HookConfig {
+            name: "hook".to_string(),
+            command: String::new(),
+            events: ["track_changed", "paused", "resumed", "stopped"]
+                .map(String::from)
+                .to_vec(),
+            timeout_secs: 10,
+            max_concurrent: 1,
+        }
+    }
+}
+
+// Shared by `[slack]` and `[mattermost]`. `base_url` defaults to the public
+// Slack API; for Mattermost it is the server URL, e.g. https://chat.example.com
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ChatStatusConfig {
+    pub enabled: bool,
+    pub token: String,
+    pub base_url: Option<String>,
+    pub template: String,
+    pub emoji: String,
+    pub retry: RetryConfig,
+}
+
+impl Default for ChatStatusConfig {
+    fn default() -> Self {
+        ChatStatusConfig {
+            enabled: false,
+            token: String::new(),
+            base_url: None,
+            template: "{name} — {artist}".to_string(),
+            emoji: "musical_note".to_string(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+// Which tracks get posted to Mastodon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MastodonPostMode {
+    EveryTrack,
+    // Only when playback moves on to a different album
+    FirstTrackOfAlbum,
+    // One post per day summarising the previous day's listening history
+    DailySummary,
+}
+
+// `summary_template` accepts {date}, {plays}, {hours}, {top_artist},
+// {top_album} and {top_track}.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MastodonConfig {
+    pub enabled: bool,
+    // Instance URL, e.g. https://mastodon.social
+    pub base_url: String,
+    pub access_token: String,
+    pub post: MastodonPostMode,
+    pub template: String,
+    pub summary_template: String,
+    // public, unlisted, private or direct
+    pub visibility: String,
+    pub attach_artwork: bool,
+    // Minimum time between two posts; posts inside the window are dropped
+    pub min_interval_secs: u64,
+    pub retry: RetryConfig,
+}
+
+impl Default for MastodonConfig {
+    fn default() -> Self {
+        MastodonConfig {
+            enabled: false,
+            base_url: String::new(),
+            access_token: String::new(),
+            post: MastodonPostMode::FirstTrackOfAlbum,
+            template: "🎵 Now listening to {album} by {artist}".to_string(),
+            summary_template: "🎧 {date}: {plays} tracks, {hours} hours. Top artist: {top_artist}"
+                .to_string(),
+            visibility: "unlisted".to_string(),
+            attach_artwork: true,
+            min_interval_secs: 900,
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+// Rules are checked in order and the first match decides; tracks matching
+// no rule are shown as-is.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PrivacyConfig {
+    // Shown instead of the track for the `generic` action
+    pub generic_text: String,
+    pub rules: Vec<PrivacyRule>,
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        PrivacyConfig {
+            generic_text: "Listening to music".to_string(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrivacyRule {
+    pub field: PrivacyField,
+    #[serde(rename = "match", default)]
+    pub match_type: PrivacyMatch,
+    pub pattern: String,
+    pub action: PrivacyAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyField {
+    Artist,
+    Album,
+    Track,
+    Genre,
+}
+
+// All match types are case-insensitive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyMatch {
+    #[default]
+    Exact,
+    Glob,
+    Regex,
+}
+
+// Ordered from least to most restrictive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyAction {
+    // Publish the track unchanged, overriding later rules
+    Allow,
+    // Drop the album name and artwork
+    HideAlbum,
+    // Replace the track with `generic_text`
+    Generic,
+    // Publish nothing, as if playback had stopped
+    Hide,
+}
+
+// Rules are checked in order and the first one covering the current time
+// selects the active profile; outside every rule no profile is active.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    // IANA name such as "Europe/Berlin"; defaults to the system time zone
+    pub timezone: Option<String>,
+    pub rules: Vec<ScheduleRule>,
+}
+
+// `days` accepts names and ranges like "mon-fri" (empty means every day).
+// Times are "HH:MM"; an `end` before `start` runs past midnight.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleRule {
+    pub profile: String,
+    #[serde(default)]
+    pub days: Vec<String>,
+    pub start: String,
+    pub end: String,
+}
+
+// Overrides applied while a profile is active; unset fields keep the
+// regular settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    // Suspends broadcasting entirely, like incognito mode
+    pub disabled: bool,
+    // Minimum privacy action applied to every track
+    pub privacy: Option<PrivacyAction>,
+    pub details: Option<String>,
+    pub state: Option<String>,
+    pub large_text: Option<String>,
+    pub buttons: Option<Vec<DiscordButton>>,
+}
+
+impl ProfileConfig {
+    pub fn discord_config(&self, base: &DiscordConfig) -> DiscordConfig {
+        DiscordConfig {
+            details: self.details.clone().unwrap_or_else(|| base.details.clone()),
+            state: self.state.clone().unwrap_or_else(|| base.state.clone()),
+            large_text: self
+                .large_text
+                .clone()
+                .unwrap_or_else(|| base.large_text.clone()),
+            buttons: self.buttons.clone().unwrap_or_else(|| base.buttons.clone()),
+            ..base.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RulesConfig {
+    // Presence rules file, next to the config file by default; a missing
+    // file means no rules
+    pub path: PathBuf,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        let path = Config::path()
+            .and_then(|path| path.parent().map(|dir| dir.join("rules.toml")))
+            .unwrap_or_default();
+        RulesConfig { path }
+    }
+}
+
+// Optional Rhai script whose `transform(track)` function may rewrite or veto
+// each track before artwork lookup. Limits keep a runaway script from
+// stalling the observer.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScriptConfig {
+    pub path: Option<PathBuf>,
+    pub timeout_ms: u64,
+    pub max_operations: u64,
+    pub max_string_size: usize,
+    pub max_collection_size: usize,
+}
+
+impl Default for ScriptConfig {
+    fn default() -> Self {
+        ScriptConfig {
+            path: None,
+            timeout_ms: 50,
+            max_operations: 100_000,
+            max_string_size: 4096,
+            max_collection_size: 256,
+        }
+    }
+}
+
+// Exponential backoff applied when a sink fails to handle an event.
+// `max_elapsed_secs = 0` disables retries.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub initial_interval_ms: u64,
+    pub max_interval_ms: u64,
+    pub max_elapsed_secs: u64,
+}
+
+impl RetryConfig {
+    pub fn disabled() -> Self {
+        RetryConfig {
+            max_elapsed_secs: 0,
+            ..RetryConfig::default()
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            initial_interval_ms: 500,
+            max_interval_ms: 10_000,
+            max_elapsed_secs: 60,
+        }
+    }
+}
+
+impl Config {
+    // Loads the config file from `$AMDRPC_CONFIG` or the user config directory,
+    // falling back to defaults when no file exists.
+    pub fn load() -> Result<Self, AppError> {
+        let path = match Self::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        };
+
+        let contents = fs::read_to_string(&path)?;
+        toml::from_str(&contents)
+            .map_err(|e| AppError::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        match std::env::var_os(CONFIG_ENV) {
+            Some(path) => Some(PathBuf::from(path)),
+            None => dirs::config_dir().map(|dir| dir.join(APP_DIR).join("config.toml")),
+        }
+    }
+}
@@ -10,6 +10,36 @@ pub enum AppError {
     NetworkError(#[from] reqwest::Error),
     #[error("Discord RPC error: {0}")]
     DiscordError(#[from] discord_presence::error::DiscordError),
+    #[error("MQTT error: {0}")]
+    MqttError(#[from] rumqttc::ClientError),
+    #[error("HTTP status {0}")]
+    HttpStatusError(u16),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("Config error: {0}")]
+    ConfigError(String),
+    #[error("Track vetoed by script")]
+    TrackVetoed,
+    #[error("Script error: {0}")]
+    ScriptError(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
     #[error("Other error: {0}")]
     Other(String),
 }
+
+impl AppError {
+    // Whether retrying the failed operation may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AppError::NetworkError(_)
+            | AppError::DiscordError(_)
+            | AppError::MqttError(_)
+            | AppError::IoError(_) => true,
+            AppError::HttpStatusError(status) => *status >= 500 || *status == 429,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::models::PlayerEvent;
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+
+// Fans player events out to every live subscriber (SSE/WebSocket clients).
+#[derive(Debug, Default)]
+pub struct EventHub {
+    subscribers: Mutex<Vec<Sender<PlayerEvent>>>,
+}
+
+pub type SharedEventHub = Arc<EventHub>;
+
+impl EventHub {
+    pub fn subscribe(&self) -> Receiver<PlayerEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(tx);
+        rx
+    }
+
+    pub fn publish(&self, event: PlayerEvent) {
+        println!("EVENT: {}", event.name());
+        // Subscribers whose receiver was dropped are pruned on send failure
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
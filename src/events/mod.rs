@@ -0,0 +1,4 @@
+pub mod hub;
+
+// Re-exports for convenient access
+pub use hub::{EventHub, SharedEventHub};
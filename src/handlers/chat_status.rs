@@ -0,0 +1,159 @@
+use crate::error::AppError;
+use crate::models::{
+    ChatStatus, MattermostCustomStatus, MattermostUser, SlackProfile, SlackProfileResponse,
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::blocking::{Client as HttpClient, RequestBuilder, Response};
+use serde_json::json;
+
+pub const SLACK_API_URL: &str = "https://slack.com/api";
+
+// Slack rejects status texts longer than 100 characters
+const SLACK_STATUS_MAX_CHARS: usize = 100;
+
+fn send_checked(request: RequestBuilder) -> Result<Response, AppError> {
+    let response = request
+        .header("User-Agent", "rust/apple-music-discord-rs")
+        .send()?;
+    if !response.status().is_success() {
+        return Err(AppError::HttpStatusError(response.status().as_u16()));
+    }
+    Ok(response)
+}
+
+fn slack_result(response: Response) -> Result<Option<SlackProfile>, AppError> {
+    let response: SlackProfileResponse = response.json()?;
+    if !response.ok {
+        return Err(AppError::Other(format!(
+            "Slack API error: {}",
+            response.error.unwrap_or_default()
+        )));
+    }
+    Ok(response.profile)
+}
+
+pub fn slack_get_status(
+    http_client: &HttpClient,
+    base_url: &str,
+    token: &str,
+) -> Result<ChatStatus, AppError> {
+    let response = send_checked(
+        http_client
+            .get(format!("{}/users.profile.get", base_url))
+            .bearer_auth(token),
+    )?;
+    let profile = slack_result(response)?.ok_or_else(|| {
+        AppError::Other("Slack API error: profile missing from response".to_string())
+    })?;
+
+    Ok(ChatStatus {
+        text: profile.status_text,
+        emoji: profile.status_emoji.trim_matches(':').to_string(),
+        expires_at: Some(profile.status_expiration).filter(|&ts| ts > 0),
+    })
+}
+
+pub fn slack_set_status(
+    http_client: &HttpClient,
+    base_url: &str,
+    token: &str,
+    status: &ChatStatus,
+) -> Result<(), AppError> {
+    let text: String = status.text.chars().take(SLACK_STATUS_MAX_CHARS).collect();
+    let emoji = if status.emoji.is_empty() {
+        String::new()
+    } else {
+        format!(":{}:", status.emoji)
+    };
+
+    let response = send_checked(
+        http_client
+            .post(format!("{}/users.profile.set", base_url))
+            .bearer_auth(token)
+            .json(&json!({
+                "profile": {
+                    "status_text": text,
+                    "status_emoji": emoji,
+                    "status_expiration": status.expires_at.unwrap_or(0),
+                }
+            })),
+    )?;
+    slack_result(response)?;
+    Ok(())
+}
+
+pub fn mattermost_get_status(
+    http_client: &HttpClient,
+    base_url: &str,
+    token: &str,
+) -> Result<Option<ChatStatus>, AppError> {
+    let user: MattermostUser = send_checked(
+        http_client
+            .get(format!("{}/api/v4/users/me", base_url))
+            .bearer_auth(token),
+    )?
+    .json()?;
+
+    let custom_status = match user.props.get("customStatus") {
+        Some(raw) if !raw.is_empty() => serde_json::from_str::<MattermostCustomStatus>(raw)?,
+        _ => return Ok(None),
+    };
+    if custom_status.text.is_empty() && custom_status.emoji.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ChatStatus {
+        text: custom_status.text,
+        emoji: custom_status.emoji,
+        expires_at: custom_status
+            .expires_at
+            .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+            .map(|ts| ts.timestamp())
+            .filter(|&ts| ts > 0),
+    }))
+}
+
+pub fn mattermost_set_status(
+    http_client: &HttpClient,
+    base_url: &str,
+    token: &str,
+    status: &ChatStatus,
+) -> Result<(), AppError> {
+    let expires_at = status
+        .expires_at
+        .and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0));
+    let body = match expires_at {
+        Some(expires_at) => json!({
+            "emoji": status.emoji,
+            "text": status.text,
+            "duration": "date_and_time",
+            "expires_at": expires_at.to_rfc3339(),
+        }),
+        None => json!({
+            "emoji": status.emoji,
+            "text": status.text,
+        }),
+    };
+
+    send_checked(
+        http_client
+            .put(format!("{}/api/v4/users/me/status/custom", base_url))
+            .bearer_auth(token)
+            .json(&body),
+    )?;
+    Ok(())
+}
+
+pub fn mattermost_clear_status(
+    http_client: &HttpClient,
+    base_url: &str,
+    token: &str,
+) -> Result<(), AppError> {
+    send_checked(
+        http_client
+            .delete(format!("{}/api/v4/users/me/status/custom", base_url))
+            .bearer_auth(token),
+    )?;
+    Ok(())
+}
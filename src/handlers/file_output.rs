@@ -0,0 +1,70 @@
+use crate::config::FileOutputConfig;
+use crate::error::AppError;
+use crate::models::{NowPlaying, NowPlayingSnapshot};
+use crate::utils::render_template;
+
+use reqwest::blocking::Client as HttpClient;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+const TEXT_FILE: &str = "now_playing.txt";
+const JSON_FILE: &str = "now_playing.json";
+const ARTWORK_FILE: &str = "artwork.jpg";
+
+pub fn write_now_playing_files(
+    config: &FileOutputConfig,
+    http_client: &HttpClient,
+    now_playing: &NowPlaying,
+) -> Result<(), AppError> {
+    fs::create_dir_all(&config.directory)?;
+
+    let text = render_template(&config.template, &now_playing.props);
+    write_atomic(&config.directory, TEXT_FILE, text.as_bytes())?;
+
+    let json = serde_json::to_vec_pretty(&NowPlayingSnapshot::new(Some(now_playing)))?;
+    write_atomic(&config.directory, JSON_FILE, &json)?;
+
+    match &now_playing.artwork_url {
+        Some(url) => {
+            let response = http_client.get(url).send()?.error_for_status()?;
+            write_atomic(&config.directory, ARTWORK_FILE, &response.bytes()?)?;
+        }
+        None => remove_if_exists(&config.directory.join(ARTWORK_FILE))?,
+    }
+
+    Ok(())
+}
+
+pub fn clear_now_playing_files(config: &FileOutputConfig) -> Result<(), AppError> {
+    if !config.directory.exists() {
+        return Ok(());
+    }
+
+    write_atomic(&config.directory, TEXT_FILE, b"")?;
+    let json = serde_json::to_vec_pretty(&NowPlayingSnapshot::new(None))?;
+    write_atomic(&config.directory, JSON_FILE, &json)?;
+    remove_if_exists(&config.directory.join(ARTWORK_FILE))?;
+
+    Ok(())
+}
+
+// Writes to a temporary sibling and renames it over the target, so readers
+// polling the file never observe a partial write.
+fn write_atomic(directory: &Path, file_name: &str, contents: &[u8]) -> Result<(), AppError> {
+    let tmp_path = directory.join(format!(".{}.tmp", file_name));
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, directory.join(file_name))?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), AppError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
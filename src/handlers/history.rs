@@ -0,0 +1,111 @@
+use crate::config::data_dir;
+use crate::error::AppError;
+use crate::models::{MusicProps, PlayRecord};
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// A play counts as skipped when less than half the track (capped at 4 minutes,
+// the usual scrobbling threshold) was listened to.
+const SKIP_THRESHOLD_SECS: f64 = 240.0;
+
+pub fn history_path() -> Result<PathBuf, AppError> {
+    Ok(data_dir().join("history.jsonl"))
+}
+
+pub fn append_play(record: &PlayRecord) -> Result<(), AppError> {
+    let path = history_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+    Ok(())
+}
+
+pub fn load_plays(from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<PlayRecord>, AppError> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut plays = Vec::new();
+    for line in BufReader::new(fs::File::open(&path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<PlayRecord>(&line) {
+            Ok(record) if record.started_at >= from && record.started_at < to => plays.push(record),
+            Ok(_) => {}
+            Err(e) => eprintln!("HISTORY: skipping malformed entry: {}", e),
+        }
+    }
+    Ok(plays)
+}
+
+// Start of the given day in local time, as used for day boundaries in stats
+pub fn local_midnight_utc(date: NaiveDate) -> Result<DateTime<Utc>, AppError> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| AppError::InvalidArgument(format!("invalid local date {}", date)))
+}
+
+// Tracks how long the current item has actually been playing, excluding pauses.
+pub struct PlayTracker {
+    props: MusicProps,
+    started_at: DateTime<Utc>,
+    listened: Duration,
+    resumed_at: Option<Instant>,
+}
+
+impl PlayTracker {
+    pub fn start(props: MusicProps) -> Self {
+        PlayTracker {
+            props,
+            started_at: Utc::now(),
+            listened: Duration::ZERO,
+            resumed_at: Some(Instant::now()),
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.listened += resumed_at.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.resumed_at.is_none() {
+            self.resumed_at = Some(Instant::now());
+        }
+    }
+
+    pub fn finish(mut self) -> PlayRecord {
+        self.pause();
+
+        let duration = self.props.duration;
+        let played_secs = if duration > 0.0 {
+            self.listened.as_secs_f64().min(duration)
+        } else {
+            self.listened.as_secs_f64()
+        };
+        let skipped = played_secs < (duration / 2.0).min(SKIP_THRESHOLD_SECS);
+
+        PlayRecord {
+            name: self.props.name,
+            artist: self.props.artist,
+            album: self.props.album,
+            duration,
+            played_secs,
+            started_at: self.started_at,
+            skipped,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::config::HookConfig;
+use crate::error::AppError;
+use crate::models::{NowPlayingEvent, NowPlayingSnapshot};
+
+use chrono::Utc;
+use serde_json::json;
+use std::io::{Read, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Environment passed to hook commands; track fields are empty when stopped.
+pub fn hook_env(event: &NowPlayingEvent) -> Vec<(&'static str, String)> {
+    let mut env = vec![("AMDRPC_EVENT", event.event.name().to_string())];
+    let Some(now_playing) = &event.now_playing else {
+        return env;
+    };
+    let props = &now_playing.props;
+    env.extend([
+        ("AMDRPC_NAME", props.name.clone()),
+        ("AMDRPC_ARTIST", props.artist.clone()),
+        ("AMDRPC_ALBUM", props.album.clone()),
+        ("AMDRPC_GENRE", props.genre.clone().unwrap_or_default()),
+        ("AMDRPC_DURATION", format!("{:.0}", props.duration)),
+        ("AMDRPC_POSITION", format!("{:.0}", now_playing.position())),
+        (
+            "AMDRPC_PLAYBACK_STATE",
+            serde_json::to_value(now_playing.playback_state)
+                .ok()
+                .and_then(|value| value.as_str().map(String::from))
+                .unwrap_or_default(),
+        ),
+        (
+            "AMDRPC_ARTWORK_URL",
+            now_playing.artwork_url.clone().unwrap_or_default(),
+        ),
+    ]);
+    env
+}
+
+// Same shape as the default webhook body
+pub fn hook_payload(event: &NowPlayingEvent) -> String {
+    json!({
+        "event": event.event.name(),
+        "timestamp": Utc::now().to_rfc3339(),
+        "now_playing": NowPlayingSnapshot::new(event.now_playing.as_ref()),
+    })
+    .to_string()
+}
+
+// Runs the hook command to completion, killing its whole process group after
+// the timeout. Anything written to stderr is logged under the hook's name.
+pub fn run_hook(
+    config: &HookConfig,
+    env: &[(&str, String)],
+    payload: &str,
+) -> Result<(), AppError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&config.command)
+        .envs(env.iter().map(|(key, value)| (*key, value)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    // Commands that ignore stdin may exit before reading it
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+    // Drain stderr concurrently so a chatty command can't block on the pipe
+    let (stderr_tx, stderr_rx) = mpsc::channel();
+    if let Some(mut stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = stderr_tx.send(output);
+        });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            // `sh` doesn't pass signals on, so kill its children as well
+            let _ = Command::new("kill")
+                .arg("-KILL")
+                .arg(format!("-{}", child.id()))
+                .status();
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    // Background processes left by the command may keep stderr open
+    if let Ok(output) = stderr_rx.recv_timeout(Duration::from_secs(1)) {
+        for line in output.lines().filter(|line| !line.trim().is_empty()) {
+            eprintln!("HOOK {}: {}", config.name, line);
+        }
+    }
+
+    match status {
+        Some(status) if status.success() => Ok(()),
+        Some(status) => Err(AppError::Other(format!("command exited with {}", status))),
+        None => Err(AppError::Other(format!(
+            "command timed out after {}s",
+            config.timeout_secs
+        ))),
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::AppError;
+use crate::models::{MastodonMediaAttachment, MastodonStatus, StatsEntry, StatsReport};
+
+use chrono::NaiveDate;
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::{Client as HttpClient, RequestBuilder, Response};
+
+pub const MASTODON_VISIBILITIES: [&str; 4] = ["public", "unlisted", "private", "direct"];
+
+// Mastodon's default limit for alt text on media attachments
+const MEDIA_DESCRIPTION_MAX_CHARS: usize = 1500;
+
+fn send_checked(request: RequestBuilder) -> Result<Response, AppError> {
+    let response = request
+        .header("User-Agent", "rust/apple-music-discord-rs")
+        .send()?;
+    if !response.status().is_success() {
+        return Err(AppError::HttpStatusError(response.status().as_u16()));
+    }
+    Ok(response)
+}
+
+pub fn mastodon_upload_media(
+    http_client: &HttpClient,
+    base_url: &str,
+    access_token: &str,
+    image: Vec<u8>,
+    description: &str,
+) -> Result<String, AppError> {
+    let description: String = description
+        .chars()
+        .take(MEDIA_DESCRIPTION_MAX_CHARS)
+        .collect();
+    let part = Part::bytes(image)
+        .file_name("artwork.jpg")
+        .mime_str("image/jpeg")?;
+    let form = Form::new()
+        .part("file", part)
+        .text("description", description);
+
+    let attachment: MastodonMediaAttachment = send_checked(
+        http_client
+            .post(format!("{}/api/v2/media", base_url))
+            .bearer_auth(access_token)
+            .multipart(form),
+    )?
+    .json()?;
+    Ok(attachment.id)
+}
+
+// `idempotency_key` lets Mastodon drop duplicates when a retried request
+// already went through.
+pub fn mastodon_post_status(
+    http_client: &HttpClient,
+    base_url: &str,
+    access_token: &str,
+    text: &str,
+    visibility: &str,
+    media_ids: &[String],
+    idempotency_key: &str,
+) -> Result<MastodonStatus, AppError> {
+    let mut form = vec![("status", text), ("visibility", visibility)];
+    for id in media_ids {
+        form.push(("media_ids[]", id));
+    }
+
+    let status = send_checked(
+        http_client
+            .post(format!("{}/api/v1/statuses", base_url))
+            .bearer_auth(access_token)
+            .header("Idempotency-Key", idempotency_key)
+            .form(&form),
+    )?
+    .json()?;
+    Ok(status)
+}
+
+pub fn render_daily_summary(template: &str, date: NaiveDate, report: &StatsReport) -> String {
+    let top = |entries: &[StatsEntry]| {
+        entries
+            .first()
+            .map(|entry| entry.label.clone())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    template
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+        .replace("{plays}", &report.total_plays.to_string())
+        .replace(
+            "{hours}",
+            &format!("{:.1}", report.total_listened_secs / 3600.0),
+        )
+        .replace("{top_artist}", &top(&report.top_artists))
+        .replace("{top_album}", &top(&report.top_albums))
+        .replace("{top_track}", &top(&report.top_tracks))
+}
@@ -1,8 +1,24 @@
+pub mod chat_status;
 pub mod discord;
+pub mod file_output;
+pub mod history;
+pub mod hook;
+pub mod mastodon;
+pub mod mqtt;
 pub mod music_artwork;
 pub mod music_player;
+pub mod now_playing;
+pub mod privacy;
+pub mod rules;
+pub mod schedule;
+pub mod script;
+pub mod stats;
+pub mod webhook;
 
 // Re-exports for convenient access
-pub use discord::update_discord_activity;
-pub use music_artwork::{get_artwork_itunes, get_artwork_musicbrainz};
-pub use music_player::get_music_props;
+pub use discord::{discord_clear_presence, discord_update_presence};
+pub use file_output::{clear_now_playing_files, write_now_playing_files};
+pub use history::{append_play, PlayTracker};
+pub use music_artwork::{get_artwork_itunes, get_artwork_musicbrainz, resolve_artwork};
+pub use music_player::{get_music_props, refresh_playback_state};
+pub use now_playing::resolve_now_playing;
@@ -0,0 +1,108 @@
+use crate::config::MqttConfig;
+use crate::models::{NowPlaying, PlaybackState};
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+pub const AVAILABILITY_ONLINE: &str = "online";
+pub const AVAILABILITY_OFFLINE: &str = "offline";
+
+pub fn mqtt_availability_topic(config: &MqttConfig) -> String {
+    format!("{}/availability", config.base_topic)
+}
+
+pub fn mqtt_state_topic(config: &MqttConfig) -> String {
+    format!("{}/state", config.base_topic)
+}
+
+pub fn mqtt_attributes_topic(config: &MqttConfig) -> String {
+    format!("{}/attributes", config.base_topic)
+}
+
+// Home Assistant media_player states
+pub fn mqtt_state_payload(now_playing: Option<&NowPlaying>) -> &'static str {
+    match now_playing.map(|np| np.playback_state) {
+        Some(PlaybackState::Playing | PlaybackState::Seeking) => "playing",
+        Some(PlaybackState::Paused | PlaybackState::Interrupted) => "paused",
+        _ => "idle",
+    }
+}
+
+// Attribute names follow Home Assistant's media_player entity
+pub fn mqtt_attributes_payload(now_playing: Option<&NowPlaying>) -> Value {
+    match now_playing {
+        Some(now_playing) => {
+            let props = &now_playing.props;
+            let position_updated_at: DateTime<Utc> = now_playing.position_updated_at.into();
+            json!({
+                "state": mqtt_state_payload(Some(now_playing)),
+                "media_title": props.name,
+                "media_artist": props.artist,
+                "media_album_name": props.album,
+                "media_duration": props.duration,
+                "media_position": props.player_position,
+                "media_position_updated_at": position_updated_at.to_rfc3339(),
+                "entity_picture": now_playing.artwork_url,
+                "artwork_provider": now_playing.artwork_provider,
+            })
+        }
+        None => json!({ "state": mqtt_state_payload(None) }),
+    }
+}
+
+// Home Assistant MQTT discovery messages as (topic, payload) pairs. Home
+// Assistant has no MQTT media_player platform, so the player is exposed as a
+// playback state sensor plus a track sensor sharing the same attributes.
+pub fn mqtt_discovery_messages(config: &MqttConfig) -> Vec<(String, Value)> {
+    let node_id = config
+        .client_id
+        .replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    let device = json!({
+        "identifiers": [node_id],
+        "name": config.device_name,
+        "manufacturer": "Apple",
+        "model": "Apple Music",
+        "sw_version": env!("CARGO_PKG_VERSION"),
+    });
+
+    let sensors = [
+        (
+            "playback_state",
+            "Playback state",
+            mqtt_state_topic(config),
+            "{{ value }}",
+            "mdi:play-pause",
+        ),
+        (
+            "now_playing",
+            "Now playing",
+            mqtt_attributes_topic(config),
+            "{{ value_json.media_title | default('') }}",
+            "mdi:music",
+        ),
+    ];
+
+    sensors
+        .into_iter()
+        .map(|(object_id, name, state_topic, value_template, icon)| {
+            let topic = format!(
+                "{}/sensor/{}/{}/config",
+                config.discovery_prefix, node_id, object_id
+            );
+            let payload = json!({
+                "name": name,
+                "unique_id": format!("{}_{}", node_id, object_id),
+                "object_id": format!("{}_{}", node_id, object_id),
+                "state_topic": state_topic,
+                "value_template": value_template,
+                "json_attributes_topic": mqtt_attributes_topic(config),
+                "availability_topic": mqtt_availability_topic(config),
+                "payload_available": AVAILABILITY_ONLINE,
+                "payload_not_available": AVAILABILITY_OFFLINE,
+                "icon": icon,
+                "device": device,
+            });
+            (topic, payload)
+        })
+        .collect()
+}
@@ -1,9 +1,25 @@
 use crate::error::AppError;
-use crate::models::{ArtworkITunesSearchResponse, ArtworkMusicBrainzResponse, MusicProps};
+use crate::models::{
+    ArtworkITunesSearchResponse, ArtworkMusicBrainzResponse, ArtworkProvider, MusicProps,
+};
 use crate::utils::{lucene_escape, remove_parentheses_content};
 use reqwest::blocking::Client as HttpClient;
 use url::form_urlencoded;
 
+// Tries each artwork provider in order, returning the first hit.
+pub fn resolve_artwork(
+    http_client: &HttpClient,
+    props: &MusicProps,
+) -> Option<(String, ArtworkProvider)> {
+    match get_artwork_itunes(http_client, props) {
+        Ok(Some(url)) => Some((url, ArtworkProvider::ITunes)),
+        _ => match get_artwork_musicbrainz(http_client, props) {
+            Ok(Some(url)) => Some((url, ArtworkProvider::MusicBrainz)),
+            _ => None,
+        },
+    }
+}
+
 pub fn get_artwork_itunes(
     http_client: &HttpClient,
     props: &MusicProps,
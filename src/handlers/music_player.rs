@@ -1,7 +1,11 @@
 use crate::error::AppError;
-use crate::models::MusicProps;
+use crate::models::{MusicProps, NowPlaying, PlaybackState, PlayerEvent};
 
-use objc2_media_player::MPMusicPlayerController;
+use objc2_media_player::{MPMediaPlayback, MPMusicPlaybackState, MPMusicPlayerController};
+use std::time::SystemTime;
+
+// Position drift beyond this while playing is reported as a seek
+const SEEK_TOLERANCE_SECS: f64 = 2.0;
 
 pub unsafe fn get_music_props(player: &MPMusicPlayerController) -> Result<MusicProps, AppError> {
     let props = match player.nowPlayingItem() {
@@ -18,13 +22,15 @@ pub unsafe fn get_music_props(player: &MPMusicPlayerController) -> Result<MusicP
                 .albumTitle()
                 .map(|s| s.to_string())
                 .ok_or_else(|| AppError::MusicPropertyError("album".to_string()))?;
+            let genre = item.genre().map(|s| s.to_string());
             let duration = item.playbackDuration();
-            let player_position = 0.0;
+            let player_position = get_player_position(player);
 
             MusicProps {
                 name,
                 artist,
                 album,
+                genre,
                 duration,
                 player_position,
             }
@@ -34,3 +40,57 @@ pub unsafe fn get_music_props(player: &MPMusicPlayerController) -> Result<MusicP
 
     Ok(props)
 }
+
+pub unsafe fn get_player_position(player: &MPMusicPlayerController) -> f64 {
+    let position = player.currentPlaybackTime();
+    if position.is_finite() {
+        position.max(0.0)
+    } else {
+        0.0
+    }
+}
+
+pub unsafe fn get_playback_state(player: &MPMusicPlayerController) -> PlaybackState {
+    match player.playbackState() {
+        MPMusicPlaybackState::Playing => PlaybackState::Playing,
+        MPMusicPlaybackState::Paused => PlaybackState::Paused,
+        MPMusicPlaybackState::Interrupted => PlaybackState::Interrupted,
+        MPMusicPlaybackState::SeekingForward | MPMusicPlaybackState::SeekingBackward => {
+            PlaybackState::Seeking
+        }
+        _ => PlaybackState::Stopped,
+    }
+}
+
+// Resamples playback state and position of the now-playing entry, returning
+// the event describing the transition, if any.
+pub unsafe fn refresh_playback_state(
+    player: &MPMusicPlayerController,
+    now_playing: &mut NowPlaying,
+) -> Option<PlayerEvent> {
+    let previous = now_playing.playback_state;
+    let expected_position = now_playing.position();
+    let playback_state = get_playback_state(player);
+    let position = get_player_position(player);
+
+    now_playing.playback_state = playback_state;
+    now_playing.props.player_position = position;
+    now_playing.position_updated_at = SystemTime::now();
+
+    match (previous, playback_state) {
+        (PlaybackState::Seeking, current) if current != PlaybackState::Seeking => {
+            Some(PlayerEvent::Seeked { position })
+        }
+        (PlaybackState::Playing, PlaybackState::Paused | PlaybackState::Interrupted) => {
+            Some(PlayerEvent::Paused { position })
+        }
+        (previous, PlaybackState::Playing) if previous != PlaybackState::Playing => {
+            Some(PlayerEvent::Resumed { position })
+        }
+        (PlaybackState::Playing, PlaybackState::Stopped) => Some(PlayerEvent::Stopped),
+        _ if (position - expected_position).abs() > SEEK_TOLERANCE_SECS => {
+            Some(PlayerEvent::Seeked { position })
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,41 @@
+use crate::error::AppError;
+use crate::handlers::music_player::get_playback_state;
+use crate::handlers::script::MetadataScript;
+use crate::handlers::{get_music_props, resolve_artwork};
+use crate::models::NowPlaying;
+
+use objc2_media_player::MPMusicPlayerController;
+use reqwest::blocking::Client as HttpClient;
+use std::time::SystemTime;
+
+// Reads the current item from the player, lets the user script rewrite or
+// veto it and resolves its artwork.
+pub unsafe fn resolve_now_playing(
+    player: &MPMusicPlayerController,
+    http_client: &HttpClient,
+    script: Option<&MetadataScript>,
+) -> Result<NowPlaying, AppError> {
+    let mut props = get_music_props(player)?;
+    if let Some(script) = script {
+        match script.transform(&props) {
+            Ok(Some(transformed)) => props = transformed,
+            Ok(None) => return Err(AppError::TrackVetoed),
+            // A broken script shouldn't hide the track
+            Err(e) => eprintln!("SCRIPT: {}", e),
+        }
+    }
+    let sampled_at = SystemTime::now();
+
+    let (artwork_url, artwork_provider) = match resolve_artwork(http_client, &props) {
+        Some((url, provider)) => (Some(url), Some(provider)),
+        None => (None, None),
+    };
+
+    Ok(NowPlaying {
+        props,
+        artwork_url,
+        artwork_provider,
+        playback_state: get_playback_state(player),
+        position_updated_at: sampled_at,
+    })
+}
@@ -0,0 +1,121 @@
+use crate::config::{PrivacyAction, PrivacyConfig, PrivacyField, PrivacyMatch};
+use crate::error::AppError;
+use crate::models::{MusicProps, NowPlayingEvent, PlayerEvent};
+use crate::utils::glob_to_regex;
+
+use regex::{Regex, RegexBuilder};
+
+struct CompiledRule {
+    field: PrivacyField,
+    pattern: Regex,
+    action: PrivacyAction,
+}
+
+// Privacy rules compiled once at startup and applied to every event before
+// it reaches a broadcasting sink.
+pub struct PrivacyFilter {
+    rules: Vec<CompiledRule>,
+    generic_text: String,
+}
+
+impl PrivacyFilter {
+    pub fn new(config: &PrivacyConfig) -> Result<Self, AppError> {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(CompiledRule {
+                    field: rule.field,
+                    pattern: compile_pattern(rule.match_type, &rule.pattern)?,
+                    action: rule.action,
+                })
+            })
+            .collect::<Result<_, AppError>>()?;
+
+        Ok(PrivacyFilter {
+            rules,
+            generic_text: config.generic_text.clone(),
+        })
+    }
+
+    pub fn action_for(&self, props: &MusicProps) -> PrivacyAction {
+        self.rules
+            .iter()
+            .find(|rule| {
+                let value = match rule.field {
+                    PrivacyField::Artist => Some(props.artist.as_str()),
+                    PrivacyField::Album => Some(props.album.as_str()),
+                    PrivacyField::Track => Some(props.name.as_str()),
+                    PrivacyField::Genre => props.genre.as_deref(),
+                };
+                value.is_some_and(|value| rule.pattern.is_match(value))
+            })
+            .map_or(PrivacyAction::Allow, |rule| rule.action)
+    }
+
+    // Returns the event as broadcasting sinks should see it, or `None` when
+    // it must not be published at all. `floor` is the least restrictive
+    // action allowed, e.g. from the active schedule profile.
+    pub fn apply(&self, event: &NowPlayingEvent, floor: PrivacyAction) -> Option<NowPlayingEvent> {
+        let now_playing = match &event.now_playing {
+            Some(now_playing) => now_playing,
+            None => return Some(event.clone()),
+        };
+
+        match self.action_for(&now_playing.props).max(floor) {
+            PrivacyAction::Allow => Some(event.clone()),
+            PrivacyAction::Hide => match event.event {
+                // Sinks clear whatever they showed for the previous track
+                PlayerEvent::TrackChanged { .. } => Some(NowPlayingEvent {
+                    event: PlayerEvent::Stopped,
+                    now_playing: None,
+                }),
+                PlayerEvent::Stopped | PlayerEvent::DiscordConnected => Some(NowPlayingEvent {
+                    event: event.event.clone(),
+                    now_playing: None,
+                }),
+                _ => None,
+            },
+            action => {
+                // The artwork would give the album away
+                if let PlayerEvent::ArtworkResolved { .. } = event.event {
+                    return None;
+                }
+
+                let mut now_playing = now_playing.clone();
+                now_playing.artwork_url = None;
+                now_playing.artwork_provider = None;
+                now_playing.props.album = String::new();
+                if action == PrivacyAction::Generic {
+                    now_playing.props.name = self.generic_text.clone();
+                    now_playing.props.artist = String::new();
+                    now_playing.props.genre = None;
+                }
+
+                let event = match event.event {
+                    PlayerEvent::TrackChanged { .. } => PlayerEvent::TrackChanged {
+                        track: now_playing.props.clone(),
+                    },
+                    ref other => other.clone(),
+                };
+                Some(NowPlayingEvent {
+                    event,
+                    now_playing: Some(now_playing),
+                })
+            }
+        }
+    }
+}
+
+// Builds the case-insensitive matcher shared by privacy and presence rules
+pub fn compile_pattern(match_type: PrivacyMatch, pattern: &str) -> Result<Regex, AppError> {
+    let regex = match match_type {
+        PrivacyMatch::Exact => format!("^{}$", regex::escape(pattern)),
+        PrivacyMatch::Glob => glob_to_regex(pattern),
+        PrivacyMatch::Regex => pattern.to_string(),
+    };
+    RegexBuilder::new(&regex)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| AppError::ConfigError(format!("invalid pattern '{}': {}", pattern, e)))
+}
@@ -0,0 +1,241 @@
+use crate::config::{
+    Config, PrivacyMatch, RangeCondition, RuleActions, RuleDefinition, RulesFile, TextCondition,
+};
+use crate::error::AppError;
+use crate::handlers::privacy::compile_pattern;
+use crate::handlers::schedule::{local_day_time, parse_timezone, TimeWindow};
+use crate::models::{ArtworkProvider, NowPlaying, PlaybackState};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+// Sinks that can be targeted besides the configured `webhook:<name>` and
+// `hook:<name>` sinks
+const SINK_NAMES: [&str; 8] = [
+    "api",
+    "discord",
+    "file",
+    "history",
+    "mastodon",
+    "mattermost",
+    "mqtt",
+    "slack",
+];
+
+struct CompiledRule {
+    name: String,
+    text: Vec<(TextField, Regex)>,
+    ranges: Vec<(RangeField, RangeCondition)>,
+    playback_state: Option<PlaybackState>,
+    source: Option<String>,
+    time: Option<TimeWindow>,
+    actions: RuleActions,
+    stop: bool,
+}
+
+#[derive(Clone, Copy)]
+enum TextField {
+    Name,
+    Artist,
+    Album,
+    Genre,
+}
+
+#[derive(Clone, Copy)]
+enum RangeField {
+    Duration,
+    PlayerPosition,
+}
+
+// Result of evaluating the rules against a track.
+#[derive(Debug, Default)]
+pub struct RuleOutcome {
+    pub fired: Vec<String>,
+    pub actions: RuleActions,
+}
+
+// Declarative presence rules, loaded and validated once at startup.
+#[derive(Default)]
+pub struct RuleEngine {
+    rules: Vec<CompiledRule>,
+    timezone: Option<Tz>,
+}
+
+impl RuleEngine {
+    pub fn load(config: &Config) -> Result<Self, AppError> {
+        let path = &config.rules.path;
+        if !path.exists() {
+            return Ok(RuleEngine::default());
+        }
+
+        let file = read_rules_file(path)?;
+        let mut known_sinks: Vec<String> = SINK_NAMES.map(String::from).to_vec();
+        known_sinks.extend(
+            config
+                .webhooks
+                .iter()
+                .map(|webhook| format!("webhook:{}", webhook.name)),
+        );
+        known_sinks.extend(
+            config
+                .hooks
+                .iter()
+                .map(|hook| format!("hook:{}", hook.name)),
+        );
+
+        let rules = file
+            .rules
+            .iter()
+            .map(|rule| {
+                compile_rule(rule, &known_sinks).map_err(|e| {
+                    let message = match e {
+                        AppError::ConfigError(message) => message,
+                        other => other.to_string(),
+                    };
+                    AppError::ConfigError(format!(
+                        "{}: rule '{}': {}",
+                        path.display(),
+                        rule.name,
+                        message
+                    ))
+                })
+            })
+            .collect::<Result<_, AppError>>()?;
+
+        Ok(RuleEngine {
+            rules,
+            timezone: parse_timezone(config.schedule.timezone.as_deref())?,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn evaluate(&self, now_playing: &NowPlaying, now: DateTime<Utc>) -> RuleOutcome {
+        let mut outcome = RuleOutcome::default();
+        for rule in &self.rules {
+            if !self.matches(rule, now_playing, now) {
+                continue;
+            }
+            outcome.fired.push(rule.name.clone());
+            outcome.actions.merge(&rule.actions);
+            if rule.stop {
+                break;
+            }
+        }
+        outcome
+    }
+
+    fn matches(&self, rule: &CompiledRule, now_playing: &NowPlaying, now: DateTime<Utc>) -> bool {
+        let props = &now_playing.props;
+        let text_matches = rule.text.iter().all(|(field, pattern)| {
+            let value = match field {
+                TextField::Name => Some(props.name.as_str()),
+                TextField::Artist => Some(props.artist.as_str()),
+                TextField::Album => Some(props.album.as_str()),
+                TextField::Genre => props.genre.as_deref(),
+            };
+            value.is_some_and(|value| pattern.is_match(value))
+        });
+        let ranges_match = rule.ranges.iter().all(|(field, range)| {
+            let value = match field {
+                RangeField::Duration => props.duration,
+                RangeField::PlayerPosition => now_playing.position(),
+            };
+            range.min.is_none_or(|min| value >= min) && range.max.is_none_or(|max| value <= max)
+        });
+        let state_matches = rule
+            .playback_state
+            .is_none_or(|state| state == now_playing.playback_state);
+        let source_matches = rule
+            .source
+            .as_deref()
+            .is_none_or(|source| source == source_name(now_playing.artwork_provider));
+        let time_matches = rule.time.as_ref().is_none_or(|window| {
+            let (day, time) = local_day_time(now, self.timezone);
+            window.covers(day, time)
+        });
+
+        text_matches && ranges_match && state_matches && source_matches && time_matches
+    }
+}
+
+pub fn read_rules_file(path: &Path) -> Result<RulesFile, AppError> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents)
+        .map_err(|e| AppError::ConfigError(format!("{}: {}", path.display(), e)))
+}
+
+fn source_name(provider: Option<ArtworkProvider>) -> &'static str {
+    match provider {
+        Some(ArtworkProvider::ITunes) => "itunes",
+        Some(ArtworkProvider::MusicBrainz) => "musicbrainz",
+        None => "none",
+    }
+}
+
+fn compile_rule(rule: &RuleDefinition, known_sinks: &[String]) -> Result<CompiledRule, AppError> {
+    let when = &rule.when;
+    let text = [
+        (TextField::Name, &when.name),
+        (TextField::Artist, &when.artist),
+        (TextField::Album, &when.album),
+        (TextField::Genre, &when.genre),
+    ]
+    .into_iter()
+    .filter_map(|(field, condition)| condition.as_ref().map(|c| (field, c)))
+    .map(|(field, condition)| {
+        let pattern = match condition {
+            TextCondition::Exact(value) => compile_pattern(PrivacyMatch::Exact, value)?,
+            TextCondition::Glob { glob } => compile_pattern(PrivacyMatch::Glob, glob)?,
+            TextCondition::Regex { regex } => compile_pattern(PrivacyMatch::Regex, regex)?,
+        };
+        Ok((field, pattern))
+    })
+    .collect::<Result<_, AppError>>()?;
+
+    let ranges = [
+        (RangeField::Duration, &when.duration),
+        (RangeField::PlayerPosition, &when.player_position),
+    ]
+    .into_iter()
+    .filter_map(|(field, range)| range.clone().map(|range| (field, range)))
+    .collect();
+
+    if let Some(source) = &when.source {
+        if !["itunes", "musicbrainz", "none"].contains(&source.as_str()) {
+            return Err(AppError::ConfigError(format!(
+                "unknown source '{}', expected itunes, musicbrainz or none",
+                source
+            )));
+        }
+    }
+    if let Some(sinks) = &rule.then.sinks {
+        if let Some(unknown) = sinks.iter().find(|sink| !known_sinks.contains(sink)) {
+            return Err(AppError::ConfigError(format!("unknown sink '{}'", unknown)));
+        }
+    }
+
+    Ok(CompiledRule {
+        name: rule.name.clone(),
+        text,
+        ranges,
+        playback_state: when.playback_state,
+        source: when.source.clone(),
+        time: when
+            .time
+            .as_ref()
+            .map(|time| TimeWindow::new(&time.days, &time.start, &time.end))
+            .transpose()?,
+        actions: rule.then.clone(),
+        stop: rule.stop,
+    })
+}
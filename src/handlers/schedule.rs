@@ -0,0 +1,138 @@
+use crate::config::{Config, ProfileConfig};
+use crate::error::AppError;
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct ActiveProfile {
+    pub name: String,
+    pub profile: ProfileConfig,
+}
+
+// Days of the week plus a daily "HH:MM" range. `days` accepts names and
+// ranges like "mon-fri" (empty means every day); an `end` before `start`
+// runs past midnight.
+pub struct TimeWindow {
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn new(days: &[String], start: &str, end: &str) -> Result<Self, AppError> {
+        let mut parsed_days = Vec::new();
+        for entry in days {
+            match entry.split_once('-') {
+                Some((from, to)) => {
+                    let (mut day, to) = (parse_weekday(from)?, parse_weekday(to)?);
+                    parsed_days.push(day);
+                    while day != to {
+                        day = day.succ();
+                        parsed_days.push(day);
+                    }
+                }
+                None => parsed_days.push(parse_weekday(entry)?),
+            }
+        }
+
+        Ok(TimeWindow {
+            days: parsed_days,
+            start: parse_time(start)?,
+            end: parse_time(end)?,
+        })
+    }
+
+    fn on_day(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    pub fn covers(&self, day: Weekday, time: NaiveTime) -> bool {
+        if self.start == self.end {
+            self.on_day(day)
+        } else if self.start < self.end {
+            self.on_day(day) && time >= self.start && time < self.end
+        } else {
+            // Overnight: the tail end belongs to the previous day's window
+            (self.on_day(day) && time >= self.start) || (self.on_day(day.pred()) && time < self.end)
+        }
+    }
+}
+
+pub fn parse_timezone(name: Option<&str>) -> Result<Option<Tz>, AppError> {
+    name.map(|name| {
+        name.parse::<Tz>()
+            .map_err(|_| AppError::ConfigError(format!("unknown timezone '{}'", name)))
+    })
+    .transpose()
+}
+
+// Weekday and wall-clock time of `now` in `timezone`, or the system time zone.
+pub fn local_day_time(now: DateTime<Utc>, timezone: Option<Tz>) -> (Weekday, NaiveTime) {
+    match timezone {
+        Some(tz) => day_time(now.with_timezone(&tz)),
+        None => day_time(now.with_timezone(&Local)),
+    }
+}
+
+fn day_time<T: Datelike + Timelike>(now: T) -> (Weekday, NaiveTime) {
+    let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), now.second()).unwrap_or_default();
+    (now.weekday(), time)
+}
+
+// Picks the presence profile for the current time from `[schedule]`,
+// validated once at startup.
+pub struct Scheduler {
+    rules: Vec<(String, TimeWindow)>,
+    timezone: Option<Tz>,
+    profiles: HashMap<String, ProfileConfig>,
+}
+
+impl Scheduler {
+    pub fn new(config: &Config) -> Result<Self, AppError> {
+        let timezone = parse_timezone(config.schedule.timezone.as_deref())?;
+
+        let mut rules = Vec::new();
+        for rule in &config.schedule.rules {
+            if !config.profiles.contains_key(&rule.profile) {
+                return Err(AppError::ConfigError(format!(
+                    "schedule refers to unknown profile '{}'",
+                    rule.profile
+                )));
+            }
+            let window = TimeWindow::new(&rule.days, &rule.start, &rule.end)?;
+            rules.push((rule.profile.clone(), window));
+        }
+
+        Ok(Scheduler {
+            rules,
+            timezone,
+            profiles: config.profiles.clone(),
+        })
+    }
+
+    pub fn active_profile(&self, now: DateTime<Utc>) -> Option<ActiveProfile> {
+        let (day, time) = local_day_time(now, self.timezone);
+        let (name, _) = self
+            .rules
+            .iter()
+            .find(|(_, window)| window.covers(day, time))?;
+        Some(ActiveProfile {
+            name: name.clone(),
+            profile: self.profiles.get(name)?.clone(),
+        })
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, AppError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| AppError::ConfigError(format!("invalid day '{}'", value)))
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, AppError> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| AppError::ConfigError(format!("invalid time '{}', expected HH:MM", value)))
+}
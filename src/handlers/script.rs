@@ -0,0 +1,137 @@
+use crate::config::ScriptConfig;
+use crate::error::AppError;
+use crate::models::MusicProps;
+
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const TRANSFORM_FN: &str = "transform";
+
+// User script loaded from `[script]`. Its `transform(track)` function gets the
+// track as a map and returns a (possibly modified) map, or `()`/`false` to
+// veto the track. The engine has no file or network access, and operation,
+// size and wall-clock limits abort runaway scripts.
+pub struct MetadataScript {
+    engine: Engine,
+    ast: AST,
+    deadline: Rc<Cell<Option<Instant>>>,
+    timeout: Duration,
+}
+
+impl MetadataScript {
+    pub fn load(config: &ScriptConfig) -> Result<Option<Self>, AppError> {
+        let Some(path) = &config.path else {
+            return Ok(None);
+        };
+
+        let deadline: Rc<Cell<Option<Instant>>> = Rc::new(Cell::new(None));
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(config.max_operations)
+            .set_max_string_size(config.max_string_size)
+            .set_max_array_size(config.max_collection_size)
+            .set_max_map_size(config.max_collection_size)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .disable_symbol("eval")
+            .on_print(|text| println!("SCRIPT: {}", text))
+            .on_debug(|text, _, _| println!("SCRIPT: {}", text));
+
+        let progress_deadline = deadline.clone();
+        engine.on_progress(move |_| match progress_deadline.get() {
+            Some(deadline) if Instant::now() > deadline => Some("timed out".into()),
+            _ => None,
+        });
+
+        let ast = engine
+            .compile_file(path.clone())
+            .map_err(|e| AppError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == TRANSFORM_FN && f.params.len() == 1)
+        {
+            return Err(AppError::ConfigError(format!(
+                "{}: script must define fn {}(track)",
+                path.display(),
+                TRANSFORM_FN
+            )));
+        }
+
+        Ok(Some(MetadataScript {
+            engine,
+            ast,
+            deadline,
+            timeout: Duration::from_millis(config.timeout_ms),
+        }))
+    }
+
+    // Runs the script on `props`; `Ok(None)` means the track was vetoed
+    pub fn transform(&self, props: &MusicProps) -> Result<Option<MusicProps>, AppError> {
+        self.deadline.set(Some(Instant::now() + self.timeout));
+        let result = self.engine.call_fn::<Dynamic>(
+            &mut Scope::new(),
+            &self.ast,
+            TRANSFORM_FN,
+            (to_map(props),),
+        );
+        self.deadline.set(None);
+
+        let result = result.map_err(|e| AppError::ScriptError(e.to_string()))?;
+        if result.is_unit() || result.as_bool() == Ok(false) {
+            return Ok(None);
+        }
+        match result.try_cast::<Map>() {
+            Some(map) => from_map(map, props).map(Some),
+            None => Err(AppError::ScriptError(format!(
+                "{} must return a map, () or false",
+                TRANSFORM_FN
+            ))),
+        }
+    }
+}
+
+fn to_map(props: &MusicProps) -> Map {
+    let mut map = Map::new();
+    map.insert("name".into(), props.name.clone().into());
+    map.insert("artist".into(), props.artist.clone().into());
+    map.insert("album".into(), props.album.clone().into());
+    map.insert(
+        "genre".into(),
+        props.genre.clone().map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map.insert("duration".into(), props.duration.into());
+    map.insert("player_position".into(), props.player_position.into());
+    map
+}
+
+// Only the text fields may be changed; timing always comes from the player
+fn from_map(mut map: Map, original: &MusicProps) -> Result<MusicProps, AppError> {
+    let mut text = |field: &str, fallback: &str| -> Result<String, AppError> {
+        match map.remove(field) {
+            None => Ok(fallback.to_string()),
+            Some(value) => value.into_string().map_err(|kind| {
+                AppError::ScriptError(format!("'{}' must be a string, got {}", field, kind))
+            }),
+        }
+    };
+    let name = text("name", &original.name)?;
+    let artist = text("artist", &original.artist)?;
+    let album = text("album", &original.album)?;
+    let genre = match map.remove("genre") {
+        None => original.genre.clone(),
+        Some(value) if value.is_unit() => None,
+        Some(value) => Some(value.into_string().map_err(|kind| {
+            AppError::ScriptError(format!("'genre' must be a string or (), got {}", kind))
+        })?),
+    };
+
+    Ok(MusicProps {
+        name,
+        artist,
+        album,
+        genre,
+        ..original.clone()
+    })
+}
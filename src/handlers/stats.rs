@@ -0,0 +1,254 @@
+use crate::models::{PlayRecord, StatsEntry, StatsReport};
+
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+pub fn build_stats_report(
+    plays: &[PlayRecord],
+    from: NaiveDate,
+    to: NaiveDate,
+    limit: usize,
+) -> StatsReport {
+    let total_plays = plays.len();
+    let total_listened_secs = plays.iter().map(|p| p.played_secs).sum();
+    let skipped = plays.iter().filter(|p| p.skipped).count();
+    let skip_rate = if total_plays > 0 {
+        skipped as f64 / total_plays as f64
+    } else {
+        0.0
+    };
+
+    let top_artists = top_entries(plays, limit, |p| p.artist.clone());
+    let top_albums = top_entries(plays, limit, |p| format!("{} - {}", p.artist, p.album));
+    let top_tracks = top_entries(plays, limit, |p| format!("{} - {}", p.artist, p.name));
+
+    let mut per_day: BTreeMap<NaiveDate, (usize, f64)> = BTreeMap::new();
+    let mut per_week: BTreeMap<(i32, u32), (usize, f64)> = BTreeMap::new();
+    for play in plays {
+        let date = play.started_at.with_timezone(&Local).date_naive();
+        let day = per_day.entry(date).or_default();
+        day.0 += 1;
+        day.1 += play.played_secs;
+
+        let iso_week = date.iso_week();
+        let week = per_week
+            .entry((iso_week.year(), iso_week.week()))
+            .or_default();
+        week.0 += 1;
+        week.1 += play.played_secs;
+    }
+
+    let (longest_streak_days, current_streak_days) =
+        listening_streaks(&per_day.keys().copied().collect(), to);
+
+    StatsReport {
+        from,
+        to,
+        total_plays,
+        total_listened_secs,
+        skip_rate,
+        longest_streak_days,
+        current_streak_days,
+        top_artists,
+        top_albums,
+        top_tracks,
+        per_day: per_day
+            .into_iter()
+            .map(|(date, (plays, listened_secs))| StatsEntry {
+                label: date.to_string(),
+                plays,
+                listened_secs,
+            })
+            .collect(),
+        per_week: per_week
+            .into_iter()
+            .map(|((year, week), (plays, listened_secs))| StatsEntry {
+                label: format!("{}-W{:02}", year, week),
+                plays,
+                listened_secs,
+            })
+            .collect(),
+    }
+}
+
+fn top_entries<F>(plays: &[PlayRecord], limit: usize, key: F) -> Vec<StatsEntry>
+where
+    F: Fn(&PlayRecord) -> String,
+{
+    let mut totals: HashMap<String, (usize, f64)> = HashMap::new();
+    for play in plays {
+        let entry = totals.entry(key(play)).or_default();
+        entry.0 += 1;
+        entry.1 += play.played_secs;
+    }
+
+    let mut entries: Vec<StatsEntry> = totals
+        .into_iter()
+        .map(|(label, (plays, listened_secs))| StatsEntry {
+            label,
+            plays,
+            listened_secs,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.plays
+            .cmp(&a.plays)
+            .then(b.listened_secs.total_cmp(&a.listened_secs))
+            .then(a.label.cmp(&b.label))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+// Returns (longest, current) runs of consecutive days with at least one play.
+// The current streak counts back from `to`, or from the day before if nothing
+// has been played yet on `to`.
+fn listening_streaks(days: &BTreeSet<NaiveDate>, to: NaiveDate) -> (u32, u32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(prev) if prev.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let mut current = 0;
+    let mut day = if days.contains(&to) {
+        Some(to)
+    } else {
+        to.pred_opt()
+    };
+    while let Some(d) = day.filter(|d| days.contains(d)) {
+        current += 1;
+        day = d.pred_opt();
+    }
+
+    (longest, current)
+}
+
+pub fn render_stats_report(report: &StatsReport, format: StatsFormat) -> String {
+    match format {
+        StatsFormat::Json => serde_json::to_string_pretty(report).unwrap_or_default(),
+        StatsFormat::Csv => render_csv(report),
+        StatsFormat::Table => render_table(report),
+    }
+}
+
+fn render_table(report: &StatsReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Listening stats {} .. {}", report.from, report.to);
+    let _ = writeln!(out, "  Plays:          {}", report.total_plays);
+    let _ = writeln!(
+        out,
+        "  Listening time: {}",
+        format_duration(report.total_listened_secs)
+    );
+    let _ = writeln!(out, "  Skip rate:      {:.1}%", report.skip_rate * 100.0);
+    let _ = writeln!(
+        out,
+        "  Streak:         {} days (longest {} days)",
+        report.current_streak_days, report.longest_streak_days
+    );
+
+    let sections = [
+        ("Top artists", &report.top_artists),
+        ("Top albums", &report.top_albums),
+        ("Top tracks", &report.top_tracks),
+        ("Per day", &report.per_day),
+        ("Per week", &report.per_week),
+    ];
+    for (title, entries) in sections {
+        let _ = writeln!(out, "\n{}", title);
+        if entries.is_empty() {
+            let _ = writeln!(out, "  (none)");
+            continue;
+        }
+        let width = entries
+            .iter()
+            .map(|e| e.label.chars().count())
+            .max()
+            .unwrap_or(0);
+        for entry in entries {
+            let _ = writeln!(
+                out,
+                "  {:<width$}  {:>5} plays  {:>9}",
+                entry.label,
+                entry.plays,
+                format_duration(entry.listened_secs),
+                width = width
+            );
+        }
+    }
+    out
+}
+
+fn render_csv(report: &StatsReport) -> String {
+    let mut out = String::from("section,label,plays,listened_secs\n");
+    let _ = writeln!(
+        out,
+        "summary,total,{},{:.0}",
+        report.total_plays, report.total_listened_secs
+    );
+    let _ = writeln!(out, "summary,skip_rate,,{:.4}", report.skip_rate);
+    let _ = writeln!(
+        out,
+        "summary,longest_streak_days,,{}",
+        report.longest_streak_days
+    );
+    let _ = writeln!(
+        out,
+        "summary,current_streak_days,,{}",
+        report.current_streak_days
+    );
+
+    let sections = [
+        ("artist", &report.top_artists),
+        ("album", &report.top_albums),
+        ("track", &report.top_tracks),
+        ("day", &report.per_day),
+        ("week", &report.per_week),
+    ];
+    for (section, entries) in sections {
+        for entry in entries {
+            let _ = writeln!(
+                out,
+                "{},{},{},{:.0}",
+                section,
+                csv_escape(&entry.label),
+                entry.plays,
+                entry.listened_secs
+            );
+        }
+    }
+    out
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let total = secs.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m {:02}s", minutes, seconds)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::config::WebhookConfig;
+use crate::error::AppError;
+use crate::models::{NowPlayingEvent, NowPlayingSnapshot};
+use crate::utils::render_json_template;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::blocking::Client as HttpClient;
+use reqwest::Method;
+use serde_json::json;
+use sha2::Sha256;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+pub fn build_webhook_body(
+    config: &WebhookConfig,
+    event: &NowPlayingEvent,
+) -> Result<String, AppError> {
+    match (&config.body_template, &event.now_playing) {
+        (Some(template), Some(now_playing)) => {
+            let body = render_json_template(template, &now_playing.props);
+            // Reject templates that don't render to valid JSON before sending
+            serde_json::from_str::<serde_json::Value>(&body)?;
+            Ok(body)
+        }
+        _ => Ok(json!({
+            "event": event.event.name(),
+            "timestamp": Utc::now().to_rfc3339(),
+            "now_playing": NowPlayingSnapshot::new(event.now_playing.as_ref()),
+        })
+        .to_string()),
+    }
+}
+
+pub fn sign_webhook_body(secret: &str, body: &str) -> Result<String, AppError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| AppError::Other(e.to_string()))?;
+    mac.update(body.as_bytes());
+    Ok(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+pub fn send_webhook(
+    http_client: &HttpClient,
+    config: &WebhookConfig,
+    event_name: &str,
+    body: &str,
+) -> Result<(), AppError> {
+    let method = Method::from_bytes(config.method.to_uppercase().as_bytes())
+        .map_err(|_| AppError::ConfigError(format!("invalid method '{}'", config.method)))?;
+
+    let mut request = http_client
+        .request(method, &config.url)
+        .header("User-Agent", "rust/apple-music-discord-rs")
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Event", event_name);
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+    if let Some(secret) = &config.secret {
+        request = request.header("X-Signature-256", sign_webhook_body(secret, body)?);
+    }
+
+    let response = request.body(body.to_string()).send()?;
+    if !response.status().is_success() {
+        return Err(AppError::HttpStatusError(response.status().as_u16()));
+    }
+    Ok(())
+}
+
+// Records a delivery that failed permanently or ran out of retries.
+pub fn append_dead_letter(
+    config: &WebhookConfig,
+    event_name: &str,
+    body: &str,
+    error: &AppError,
+) -> Result<(), AppError> {
+    if let Some(parent) = config.dead_letter_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let entry = json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "webhook": config.name,
+        "url": config.url,
+        "method": config.method,
+        "event": event_name,
+        "body": body,
+        "error": error.to_string(),
+    });
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config.dead_letter_path)?;
+    writeln!(file, "{}", entry)?;
+    Ok(())
+}
@@ -1,5 +1,10 @@
+pub mod cli;
+pub mod config;
 pub mod error;
+pub mod events;
 pub mod handlers;
 pub mod models;
 pub mod observer;
+pub mod server;
+pub mod sinks;
 pub mod utils;
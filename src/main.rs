@@ -1,18 +1,64 @@
+mod cli;
+mod config;
 mod error;
+mod events;
 mod handlers;
 mod models;
 mod observer;
+mod server;
+mod sinks;
 mod utils;
 
+use chrono::Utc;
 use std::error::Error;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use objc2::rc::autoreleasepool;
 use objc2_foundation::{NSDate, NSDefaultRunLoopMode, NSPort, NSRunLoop};
 use objc2_media_player::MPMusicPlayerController;
 
+use config::Config;
+use events::EventHub;
+use handlers::privacy::PrivacyFilter;
+use handlers::rules::RuleEngine;
+use handlers::schedule::Scheduler;
+use handlers::script::MetadataScript;
+use models::PlayerState;
+use sinks::SinkBus;
+
 fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("stats") => return Ok(cli::run_stats(&args[1..])?),
+        Some("incognito") => return Ok(cli::run_incognito(&args[1..])?),
+        Some("rules") => return Ok(cli::run_rules(&args[1..])?),
+        Some(other) => return Err(format!("unknown command '{}'", other).into()),
+        None => {}
+    }
+
+    let config = Config::load()?;
+    let state = Arc::new(RwLock::new(PlayerState::default()));
+    let events = Arc::new(EventHub::default());
+
+    let scheduler = Scheduler::new(&config)?;
+    let script = MetadataScript::load(&config.script)?;
+    let mut bus = SinkBus::new(
+        PrivacyFilter::new(&config.privacy)?,
+        RuleEngine::load(&config)?,
+    );
+    let publisher = bus.publisher();
+    publisher.set_profile(scheduler.active_profile(Utc::now()));
+    if config.api.enabled {
+        server::spawn_api_server(
+            &config.api,
+            state.clone(),
+            events.clone(),
+            publisher.clone(),
+        )?;
+    }
+    sinks::register_sinks(&mut bus, &config, state, events)?;
+
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
 
@@ -24,7 +70,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     unsafe {
         println!("DEBUG: Registering Observer");
         let dummy_player = MPMusicPlayerController::systemMusicPlayer();
-        let _observer = observer::MusicPlayerObserver::new();
+        let _observer = observer::MusicPlayerObserver::new(publisher.clone(), script);
 
         let run_loop = NSRunLoop::currentRunLoop();
 
@@ -43,10 +89,16 @@ fn main() -> Result<(), Box<dyn Error>> {
                 _ = dummy_player.indexOfNowPlayingItem();
             });
 
+            publisher.expire_incognito();
+            publisher.set_profile(scheduler.active_profile(Utc::now()));
+
             // Optional: Small sleep to prevent CPU spinning
             // std::thread::sleep(std::time::Duration::from_millis(100));
         }
     }
 
+    // Let every sink flush and clean up (clear Discord activity, close history)
+    bus.shutdown();
+
     Ok(())
 }
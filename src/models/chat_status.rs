@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// A chat custom status, normalized across Slack and Mattermost
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatStatus {
+    pub text: String,
+    // Emoji name without colons, e.g. "musical_note"
+    pub emoji: String,
+    // Unix timestamp; `None` means the status never expires
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlackProfileResponse {
+    pub ok: bool,
+    pub error: Option<String>,
+    pub profile: Option<SlackProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlackProfile {
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub status_emoji: String,
+    #[serde(default)]
+    pub status_expiration: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MattermostUser {
+    #[serde(default)]
+    pub props: HashMap<String, String>,
+}
+
+// Stored as a JSON string in the user's `customStatus` prop
+#[derive(Debug, Deserialize)]
+pub struct MattermostCustomStatus {
+    #[serde(default)]
+    pub emoji: String,
+    #[serde(default)]
+    pub text: String,
+    pub expires_at: Option<String>,
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// While active, nothing is broadcast; `until` is `None` when incognito stays
+// on until turned off explicitly.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct IncognitoStatus {
+    pub active: bool,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl IncognitoStatus {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.active && self.until.is_some_and(|until| until <= now)
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct MastodonMediaAttachment {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MastodonStatus {
+    pub id: String,
+    pub url: Option<String>,
+}
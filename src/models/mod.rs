@@ -1,6 +1,24 @@
+pub mod chat_status;
+pub mod incognito;
+pub mod mastodon;
 pub mod music_artwork;
 pub mod music_props;
+pub mod now_playing;
+pub mod play_record;
+pub mod player_event;
+pub mod stats;
 
 // Re-exports for convenient access
+pub use chat_status::{
+    ChatStatus, MattermostCustomStatus, MattermostUser, SlackProfile, SlackProfileResponse,
+};
+pub use incognito::IncognitoStatus;
+pub use mastodon::{MastodonMediaAttachment, MastodonStatus};
 pub use music_artwork::{ArtworkITunesSearchResponse, ArtworkMusicBrainzResponse};
 pub use music_props::MusicProps;
+pub use now_playing::{
+    ArtworkProvider, NowPlaying, NowPlayingSnapshot, PlaybackState, PlayerState, SharedPlayerState,
+};
+pub use play_record::PlayRecord;
+pub use player_event::{NowPlayingEvent, PlayerEvent};
+pub use stats::{StatsEntry, StatsReport};
@@ -1,8 +1,11 @@
-#[derive(Debug, Clone)]
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct MusicProps {
     pub name: String,
     pub artist: String,
     pub album: String,
+    pub genre: Option<String>,
     pub duration: f64,
     pub player_position: f64,
 }
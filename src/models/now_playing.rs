@@ -0,0 +1,100 @@
+use crate::models::MusicProps;
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackState {
+    Stopped,
+    Playing,
+    Paused,
+    Interrupted,
+    Seeking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtworkProvider {
+    ITunes,
+    MusicBrainz,
+}
+
+#[derive(Debug, Clone)]
+pub struct NowPlaying {
+    pub props: MusicProps,
+    pub artwork_url: Option<String>,
+    pub artwork_provider: Option<ArtworkProvider>,
+    pub playback_state: PlaybackState,
+    // When `props.player_position` was sampled from the player
+    pub position_updated_at: SystemTime,
+}
+
+impl NowPlaying {
+    // Current playback position, extrapolated from the last sample while playing.
+    pub fn position(&self) -> f64 {
+        let mut position = self.props.player_position;
+        if self.playback_state == PlaybackState::Playing {
+            position += SystemTime::now()
+                .duration_since(self.position_updated_at)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or_default();
+        }
+        if self.props.duration > 0.0 {
+            position.min(self.props.duration)
+        } else {
+            position
+        }
+    }
+
+    pub fn progress(&self) -> f64 {
+        if self.props.duration > 0.0 {
+            self.position() / self.props.duration
+        } else {
+            0.0
+        }
+    }
+}
+
+// Serializable view of the current track, shared by the HTTP API and file output.
+#[derive(Debug, Serialize)]
+pub struct NowPlayingSnapshot {
+    pub track: Option<MusicProps>,
+    pub artwork_url: Option<String>,
+    pub artwork_provider: Option<ArtworkProvider>,
+    pub playback_state: PlaybackState,
+    pub position: f64,
+    pub progress: f64,
+}
+
+impl NowPlayingSnapshot {
+    pub fn new(now_playing: Option<&NowPlaying>) -> Self {
+        match now_playing {
+            Some(now_playing) => NowPlayingSnapshot {
+                track: Some(now_playing.props.clone()),
+                artwork_url: now_playing.artwork_url.clone(),
+                artwork_provider: now_playing.artwork_provider,
+                playback_state: now_playing.playback_state,
+                position: now_playing.position(),
+                progress: now_playing.progress(),
+            },
+            None => NowPlayingSnapshot {
+                track: None,
+                artwork_url: None,
+                artwork_provider: None,
+                playback_state: PlaybackState::Stopped,
+                position: 0.0,
+                progress: 0.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PlayerState {
+    pub now_playing: Option<NowPlaying>,
+    pub discord_connected: bool,
+}
+
+pub type SharedPlayerState = Arc<RwLock<PlayerState>>;
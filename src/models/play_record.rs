@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayRecord {
+    pub name: String,
+    pub artist: String,
+    pub album: String,
+    pub duration: f64,
+    pub played_secs: f64,
+    pub started_at: DateTime<Utc>,
+    pub skipped: bool,
+}
@@ -0,0 +1,48 @@
+use crate::models::{ArtworkProvider, MusicProps, NowPlaying};
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlayerEvent {
+    TrackChanged {
+        track: MusicProps,
+    },
+    ArtworkResolved {
+        artwork_url: String,
+        artwork_provider: ArtworkProvider,
+    },
+    Paused {
+        position: f64,
+    },
+    Resumed {
+        position: f64,
+    },
+    Seeked {
+        position: f64,
+    },
+    Stopped,
+    DiscordConnected,
+}
+
+impl PlayerEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayerEvent::TrackChanged { .. } => "track_changed",
+            PlayerEvent::ArtworkResolved { .. } => "artwork_resolved",
+            PlayerEvent::Paused { .. } => "paused",
+            PlayerEvent::Resumed { .. } => "resumed",
+            PlayerEvent::Seeked { .. } => "seeked",
+            PlayerEvent::Stopped => "stopped",
+            PlayerEvent::DiscordConnected => "discord_connected",
+        }
+    }
+}
+
+// What the observer publishes to output sinks: the event itself plus the
+// resolved now-playing state right after it.
+#[derive(Debug, Clone)]
+pub struct NowPlayingEvent {
+    pub event: PlayerEvent,
+    pub now_playing: Option<NowPlaying>,
+}
@@ -0,0 +1,25 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total_plays: usize,
+    pub total_listened_secs: f64,
+    pub skip_rate: f64,
+    pub longest_streak_days: u32,
+    pub current_streak_days: u32,
+    pub top_artists: Vec<StatsEntry>,
+    pub top_albums: Vec<StatsEntry>,
+    pub top_tracks: Vec<StatsEntry>,
+    pub per_day: Vec<StatsEntry>,
+    pub per_week: Vec<StatsEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsEntry {
+    pub label: String,
+    pub plays: usize,
+    pub listened_secs: f64,
+}
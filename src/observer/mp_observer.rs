@@ -1,6 +1,9 @@
-use crate::handlers::update_discord_activity;
+use crate::error::AppError;
+use crate::handlers::script::MetadataScript;
+use crate::handlers::{refresh_playback_state, resolve_now_playing};
+use crate::models::{NowPlaying, PlayerEvent};
+use crate::sinks::BusPublisher;
 
-use discord_presence::Client;
 use objc2::rc::Retained;
 use objc2::{define_class, msg_send, sel, AllocAnyThread, DeclaredClass};
 use reqwest::blocking::{Client as HttpClient, ClientBuilder};
@@ -18,8 +21,10 @@ pub struct MusicPlayerObserverIvars {
     playback_state_notification: Retained<NSString>,
     now_playing_item_notification: Retained<NSString>,
     http_client: HttpClient,
-    discord_client: RefCell<Client>,
     previous_index: RefCell<MPMediaEntityPersistentID>,
+    now_playing: RefCell<Option<NowPlaying>>,
+    publisher: BusPublisher,
+    script: Option<MetadataScript>,
 }
 
 define_class!(
@@ -50,14 +55,8 @@ define_class!(
                             // Store the new ID
                             *self.ivars().previous_index.borrow_mut() = item.persistentID();
 
-                            //Update activity on changes
-                            if let Err(e) = update_discord_activity(
-                                player,
-                                &mut self.ivars().discord_client.borrow_mut(),
-                                &self.ivars().http_client,
-                            ) {
-                                eprintln!("DISCOR_RPC: error in discord_update_activity: {}", e);
-                            }
+                            //Publish the resolved track to every sink
+                            self.publish_track_change(player);
                         }
 
                         println!(
@@ -72,6 +71,14 @@ define_class!(
                     }
                     None => {
                         println!("<--->    -- No Playing Item");
+                        self.publish_stopped();
+                    }
+                }
+
+                // Publish pause/resume/seek transitions of the current item
+                if let Some(current) = self.ivars().now_playing.borrow_mut().as_mut() {
+                    if let Some(event) = refresh_playback_state(player, current) {
+                        self.ivars().publisher.publish(event, Some(current.clone()));
                     }
                 }
 
@@ -137,7 +144,7 @@ define_class!(
 );
 
 impl MusicPlayerObserver {
-    pub unsafe fn new() -> Retained<Self> {
+    pub unsafe fn new(publisher: BusPublisher, script: Option<MetadataScript>) -> Retained<Self> {
         let observer = Self::alloc().set_ivars(MusicPlayerObserverIvars {
             object: MPMusicPlayerController::systemMusicPlayer(),
             playback_state_notification: ns_string!(
@@ -148,26 +155,17 @@ impl MusicPlayerObserver {
                 "MPMusicPlayerControllerNowPlayingItemDidChangeNotification"
             )
             .copy(),
-            discord_client: RefCell::new(Client::new(773825528921849856)),
             http_client: ClientBuilder::new()
                 .timeout(Duration::from_secs(10))
                 .build()
                 .unwrap(),
             previous_index: RefCell::new(MPMediaEntityPersistentID::from_be(0)),
+            now_playing: RefCell::new(None),
+            publisher,
+            script,
         });
         let observer: Retained<Self> = unsafe { msg_send![super(observer), init] };
 
-        observer
-            .ivars()
-            .discord_client
-            .borrow()
-            .on_ready(|_ctx| {
-                println!("Discord RPC connected!");
-            })
-            .persist();
-
-        observer.ivars().discord_client.borrow_mut().start();
-
         // Add notification observers
         unsafe {
             let notification_center = NSNotificationCenter::defaultCenter();
@@ -197,17 +195,61 @@ impl MusicPlayerObserver {
 
         observer
     }
+
+    unsafe fn publish_track_change(&self, player: &MPMusicPlayerController) {
+        match resolve_now_playing(
+            player,
+            &self.ivars().http_client,
+            self.ivars().script.as_ref(),
+        ) {
+            Ok(now_playing) => {
+                *self.ivars().now_playing.borrow_mut() = Some(now_playing.clone());
+
+                let publisher = &self.ivars().publisher;
+                publisher.publish(
+                    PlayerEvent::TrackChanged {
+                        track: now_playing.props.clone(),
+                    },
+                    Some(now_playing.clone()),
+                );
+                if let (Some(artwork_url), Some(artwork_provider)) = (
+                    now_playing.artwork_url.clone(),
+                    now_playing.artwork_provider,
+                ) {
+                    publisher.publish(
+                        PlayerEvent::ArtworkResolved {
+                            artwork_url,
+                            artwork_provider,
+                        },
+                        Some(now_playing),
+                    );
+                }
+            }
+            Err(AppError::NoSongPlaying) => self.publish_stopped(),
+            Err(AppError::TrackVetoed) => {
+                // Keep the item id so the vetoed track isn't re-resolved on
+                // every playback state change
+                println!("OBSERVER: track vetoed by script");
+                if self.ivars().now_playing.borrow_mut().take().is_some() {
+                    self.ivars().publisher.publish(PlayerEvent::Stopped, None);
+                }
+            }
+            Err(e) => eprintln!("OBSERVER: error in resolve_now_playing: {}", e),
+        }
+    }
+
+    fn publish_stopped(&self) {
+        // Forget the last item so replaying it is reported as a new track
+        *self.ivars().previous_index.borrow_mut() = MPMediaEntityPersistentID::from_be(0);
+        if self.ivars().now_playing.borrow_mut().take().is_some() {
+            self.ivars().publisher.publish(PlayerEvent::Stopped, None);
+        }
+    }
 }
 
 impl Drop for MusicPlayerObserver {
     fn drop(&mut self) {
         unsafe {
-            //Clear Discord Activity
-            if let Err(e) = self.ivars().discord_client.borrow_mut().clear_activity() {
-                eprintln!("DEBUG: error in clear_activity: {}", e);
-            };
-            println!("Disconnected from Discord RPC.");
-
             // Remove notification observers
             let notification_center = NSNotificationCenter::defaultCenter();
             notification_center.removeObserver_name_object(
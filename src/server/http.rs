@@ -0,0 +1,136 @@
+use crate::config::ApiConfig;
+use crate::error::AppError;
+use crate::events::SharedEventHub;
+use crate::models::{NowPlayingSnapshot, SharedPlayerState};
+use crate::server::push::{spawn_sse_stream, spawn_websocket_stream};
+use crate::sinks::BusPublisher;
+use crate::utils::parse_duration;
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::sync::PoisonError;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Self-contained "now playing" widget for OBS browser sources
+const OVERLAY_HTML: &str = include_str!("overlay.html");
+
+pub fn spawn_api_server(
+    config: &ApiConfig,
+    state: SharedPlayerState,
+    events: SharedEventHub,
+    publisher: BusPublisher,
+) -> Result<JoinHandle<()>, AppError> {
+    let address = format!("{}:{}", config.bind, config.port);
+    let server = Server::http(&address)
+        .map_err(|e| AppError::Other(format!("failed to bind API server on {}: {}", address, e)))?;
+    println!("API: listening on http://{}", address);
+
+    let started_at = Instant::now();
+    let handle = thread::Builder::new()
+        .name("api-server".to_string())
+        .spawn(move || {
+            for request in server.incoming_requests() {
+                if let Err(e) = handle_request(request, &state, &events, &publisher, started_at) {
+                    eprintln!("API: error while responding: {}", e);
+                }
+            }
+        })?;
+
+    Ok(handle)
+}
+
+fn handle_request(
+    request: Request,
+    state: &SharedPlayerState,
+    events: &SharedEventHub,
+    publisher: &BusPublisher,
+    started_at: Instant,
+) -> Result<(), AppError> {
+    // Ignore the query string when routing
+    let (path, query) = match request.url().split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (request.url().to_string(), String::new()),
+    };
+
+    let (status, body) = match (request.method(), path.as_str()) {
+        (Method::Get, "/now-playing") => (200, now_playing_body(state)),
+        (Method::Get, "/health") => (200, health_body(state, started_at)),
+        (Method::Get, "/incognito") => (200, json!(publisher.incognito())),
+        (Method::Post, "/incognito") => enable_incognito(publisher, &query),
+        (Method::Delete, "/incognito") => {
+            publisher.clear_incognito();
+            (200, json!(publisher.incognito()))
+        }
+        (Method::Get, "/overlay") => {
+            let response = Response::from_string(OVERLAY_HTML)
+                .with_header(header("Content-Type", "text/html; charset=utf-8"))
+                .with_header(header("Cache-Control", "no-store"));
+            request.respond(response)?;
+            return Ok(());
+        }
+        // Streaming endpoints take over the connection on their own thread
+        (Method::Get, "/events") => return spawn_sse_stream(request, state, events.subscribe()),
+        (Method::Get, "/ws") => return spawn_websocket_stream(request, state, events.subscribe()),
+        (_, "/now-playing" | "/health" | "/events" | "/ws" | "/overlay" | "/incognito") => {
+            (405, json!({ "error": "method not allowed" }))
+        }
+        _ => (404, json!({ "error": "not found" })),
+    };
+
+    request.respond(json_response(status, &body))?;
+    Ok(())
+}
+
+fn now_playing_body(state: &SharedPlayerState) -> Value {
+    let state = state.read().unwrap_or_else(PoisonError::into_inner);
+    json!(NowPlayingSnapshot::new(state.now_playing.as_ref()))
+}
+
+// `POST /incognito?for=30m` turns incognito on for a while; without `for` it
+// stays on until `DELETE /incognito`.
+fn enable_incognito(publisher: &BusPublisher, query: &str) -> (u16, Value) {
+    let duration = url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "for")
+        .map(|(_, value)| value.into_owned());
+
+    let until = match duration {
+        Some(value) => {
+            match parse_duration(&value).and_then(|d| chrono::Duration::from_std(d).ok()) {
+                Some(duration) => Some(Utc::now() + duration),
+                None => {
+                    return (
+                        400,
+                        json!({ "error": format!("invalid duration '{}'", value) }),
+                    )
+                }
+            }
+        }
+        None => None,
+    };
+    publisher.set_incognito(until);
+    (200, json!(publisher.incognito()))
+}
+
+fn health_body(state: &SharedPlayerState, started_at: Instant) -> Value {
+    let state = state.read().unwrap_or_else(PoisonError::into_inner);
+    json!({
+        "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_secs": started_at.elapsed().as_secs(),
+        "discord_connected": state.discord_connected,
+    })
+}
+
+pub(crate) fn json_response(status: u16, body: &Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_data(body.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+        .with_header(header("Cache-Control", "no-store"))
+}
+
+pub(crate) fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
@@ -0,0 +1,5 @@
+pub mod http;
+pub mod push;
+
+// Re-exports for convenient access
+pub use http::spawn_api_server;
@@ -0,0 +1,165 @@
+use crate::error::AppError;
+use crate::models::{PlayerEvent, SharedPlayerState};
+use crate::server::http::header;
+
+use std::io::Write;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::PoisonError;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Request, Response};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+// Idle connections get a keep-alive so proxies and OBS don't drop them
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+// Events that describe the current track, so new clients don't wait for the next change.
+fn snapshot_events(state: &SharedPlayerState) -> Vec<PlayerEvent> {
+    let state = state.read().unwrap_or_else(PoisonError::into_inner);
+    let mut events = Vec::new();
+    if state.discord_connected {
+        events.push(PlayerEvent::DiscordConnected);
+    }
+    if let Some(now_playing) = &state.now_playing {
+        events.push(PlayerEvent::TrackChanged {
+            track: now_playing.props.clone(),
+        });
+        if let (Some(artwork_url), Some(artwork_provider)) =
+            (&now_playing.artwork_url, now_playing.artwork_provider)
+        {
+            events.push(PlayerEvent::ArtworkResolved {
+                artwork_url: artwork_url.clone(),
+                artwork_provider,
+            });
+        }
+    }
+    events
+}
+
+pub fn spawn_sse_stream(
+    request: Request,
+    state: &SharedPlayerState,
+    events: Receiver<PlayerEvent>,
+) -> Result<(), AppError> {
+    let snapshot = snapshot_events(state);
+    thread::Builder::new()
+        .name("api-sse".to_string())
+        .spawn(move || {
+            let mut writer = request.into_writer();
+            if let Err(e) = stream_sse(&mut writer, snapshot, events) {
+                println!("API: SSE client disconnected: {}", e);
+            }
+        })?;
+    Ok(())
+}
+
+fn stream_sse(
+    writer: &mut impl Write,
+    snapshot: Vec<PlayerEvent>,
+    events: Receiver<PlayerEvent>,
+) -> Result<(), AppError> {
+    // tiny_http buffers chunked bodies, so the response is written by hand
+    // and flushed after every event.
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: text/event-stream\r\n\
+         Cache-Control: no-store\r\n\
+         Connection: keep-alive\r\n\
+         Access-Control-Allow-Origin: *\r\n\r\n"
+    )?;
+    writer.flush()?;
+
+    for event in snapshot {
+        write_sse_event(writer, &event)?;
+    }
+
+    loop {
+        match events.recv_timeout(KEEP_ALIVE_INTERVAL) {
+            Ok(event) => write_sse_event(writer, &event)?,
+            Err(RecvTimeoutError::Timeout) => {
+                writer.write_all(b": keep-alive\n\n")?;
+                writer.flush()?;
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+fn write_sse_event(writer: &mut impl Write, event: &PlayerEvent) -> Result<(), AppError> {
+    write!(
+        writer,
+        "event: {}\ndata: {}\n\n",
+        event.name(),
+        serde_json::to_string(event)?
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn spawn_websocket_stream(
+    request: Request,
+    state: &SharedPlayerState,
+    events: Receiver<PlayerEvent>,
+) -> Result<(), AppError> {
+    let key = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Sec-WebSocket-Key"))
+        .map(|h| h.value.as_str().to_string());
+    let key = match key {
+        Some(key) => key,
+        None => {
+            request.respond(
+                Response::from_string("missing Sec-WebSocket-Key").with_status_code(400),
+            )?;
+            return Ok(());
+        }
+    };
+
+    let snapshot = snapshot_events(state);
+    thread::Builder::new()
+        .name("api-websocket".to_string())
+        .spawn(move || {
+            let response = Response::empty(101).with_header(header(
+                "Sec-WebSocket-Accept",
+                &derive_accept_key(key.as_bytes()),
+            ));
+            let stream = request.upgrade("websocket", response);
+            let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+            if let Err(e) = stream_websocket(&mut socket, snapshot, events) {
+                println!("API: WebSocket client disconnected: {}", e);
+            }
+            let _ = socket.close(None);
+        })?;
+    Ok(())
+}
+
+fn stream_websocket<S: std::io::Read + Write>(
+    socket: &mut WebSocket<S>,
+    snapshot: Vec<PlayerEvent>,
+    events: Receiver<PlayerEvent>,
+) -> Result<(), AppError> {
+    let send = |socket: &mut WebSocket<S>, event: &PlayerEvent| -> Result<(), AppError> {
+        let payload = serde_json::to_string(event)?;
+        socket
+            .send(Message::Text(payload))
+            .map_err(|e| AppError::Other(e.to_string()))
+    };
+
+    for event in &snapshot {
+        send(socket, event)?;
+    }
+
+    loop {
+        match events.recv_timeout(KEEP_ALIVE_INTERVAL) {
+            Ok(event) => send(socket, &event)?,
+            Err(RecvTimeoutError::Timeout) => socket
+                .send(Message::Ping(Vec::new()))
+                .map_err(|e| AppError::Other(e.to_string()))?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
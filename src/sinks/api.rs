@@ -0,0 +1,36 @@
+use crate::error::AppError;
+use crate::events::SharedEventHub;
+use crate::models::{NowPlayingEvent, PlayerEvent, SharedPlayerState};
+use crate::sinks::OutputSink;
+
+use std::sync::PoisonError;
+
+// Mirrors events into the state served by the HTTP API and its push streams.
+pub struct ApiSink {
+    state: SharedPlayerState,
+    events: SharedEventHub,
+}
+
+impl ApiSink {
+    pub fn new(state: SharedPlayerState, events: SharedEventHub) -> Self {
+        ApiSink { state, events }
+    }
+}
+
+impl OutputSink for ApiSink {
+    fn name(&self) -> &str {
+        "api"
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        {
+            let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+            match event.event {
+                PlayerEvent::DiscordConnected => state.discord_connected = true,
+                _ => state.now_playing = event.now_playing.clone(),
+            }
+        }
+        self.events.publish(event.event.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,294 @@
+use crate::config::{PrivacyAction, RetryConfig};
+use crate::error::AppError;
+use crate::handlers::privacy::PrivacyFilter;
+use crate::handlers::rules::RuleEngine;
+use crate::handlers::schedule::ActiveProfile;
+use crate::models::{IncognitoStatus, NowPlaying, NowPlayingEvent, PlayerEvent};
+use crate::sinks::OutputSink;
+use crate::utils::retry_with_policy;
+
+use chrono::{DateTime, Utc};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+
+struct SinkChannel {
+    name: String,
+    bypass_privacy: bool,
+    bypass_incognito: bool,
+    tx: Sender<NowPlayingEvent>,
+}
+
+// Runtime switches that decide what broadcasting sinks get to see.
+#[derive(Default)]
+struct BusState {
+    incognito: IncognitoStatus,
+    profile: Option<ActiveProfile>,
+    // Last published state, replayed to sinks when they resume
+    current: Option<NowPlaying>,
+}
+
+impl BusState {
+    fn suspended(&self) -> bool {
+        self.incognito.active || self.profile.as_ref().is_some_and(|p| p.profile.disabled)
+    }
+
+    fn privacy_floor(&self) -> PrivacyAction {
+        self.profile
+            .as_ref()
+            .and_then(|p| p.profile.privacy)
+            .unwrap_or(PrivacyAction::Allow)
+    }
+
+    fn profile_name(&self) -> Option<&str> {
+        self.profile.as_ref().map(|p| p.name.as_str())
+    }
+}
+
+// Cheap, cloneable handle for publishing onto the bus from any thread.
+#[derive(Clone)]
+pub struct BusPublisher {
+    senders: Arc<Mutex<Vec<SinkChannel>>>,
+    privacy: Arc<PrivacyFilter>,
+    rules: Arc<RuleEngine>,
+    state: Arc<Mutex<BusState>>,
+}
+
+impl BusPublisher {
+    pub fn publish(&self, event: PlayerEvent, now_playing: Option<NowPlaying>) {
+        let event = NowPlayingEvent { event, now_playing };
+        let (suspended, floor) = {
+            let mut state = self.lock_state();
+            if !matches!(event.event, PlayerEvent::DiscordConnected) {
+                state.current = event.now_playing.clone();
+            }
+            (state.suspended(), state.privacy_floor())
+        };
+        self.dispatch(&event, floor, |sink| !suspended || sink.bypass_incognito);
+    }
+
+    pub fn rules(&self) -> Arc<RuleEngine> {
+        self.rules.clone()
+    }
+
+    pub fn incognito(&self) -> IncognitoStatus {
+        self.lock_state().incognito
+    }
+
+    // Suspends broadcasting sinks until `until`, or until turned off when
+    // `None`. Sinks clear their output as if playback had stopped.
+    pub fn set_incognito(&self, until: Option<DateTime<Utc>>) {
+        self.update(|state| {
+            state.incognito = IncognitoStatus {
+                active: true,
+                until,
+            }
+        });
+    }
+
+    pub fn clear_incognito(&self) {
+        self.update(|state| state.incognito = IncognitoStatus::default());
+    }
+
+    // Called periodically to end timed incognito sessions
+    pub fn expire_incognito(&self) {
+        if self.incognito().is_expired(Utc::now()) {
+            self.clear_incognito();
+        }
+    }
+
+    pub fn profile(&self) -> Option<ActiveProfile> {
+        self.lock_state().profile.clone()
+    }
+
+    pub fn set_profile(&self, profile: Option<ActiveProfile>) {
+        self.update(|state| state.profile = profile);
+    }
+
+    // Applies a runtime switch and brings the affected sinks up to date:
+    // newly suspended sinks are cleared, resumed or reconfigured ones get the
+    // current track again.
+    fn update(&self, change: impl FnOnce(&mut BusState)) {
+        let (was_suspended, suspended, profile_changed, floor, current) = {
+            let mut state = self.lock_state();
+            let was_suspended = state.suspended();
+            let was_incognito = state.incognito.active;
+            let previous_profile = state.profile_name().map(str::to_string);
+
+            change(&mut state);
+
+            if state.incognito.active != was_incognito {
+                let label = if state.incognito.active { "on" } else { "off" };
+                println!("INCOGNITO: {}", label);
+            }
+            let profile_changed = state.profile_name() != previous_profile.as_deref();
+            if profile_changed {
+                println!("PROFILE: {}", state.profile_name().unwrap_or("default"));
+            }
+            (
+                was_suspended,
+                state.suspended(),
+                profile_changed,
+                state.privacy_floor(),
+                state.current.clone(),
+            )
+        };
+
+        if suspended && !was_suspended {
+            let stopped = NowPlayingEvent {
+                event: PlayerEvent::Stopped,
+                now_playing: None,
+            };
+            self.send(|sink| (!sink.bypass_incognito).then_some(&stopped));
+            return;
+        }
+
+        let resumed = was_suspended && !suspended;
+        let now_playing = match current {
+            Some(now_playing) if resumed || (profile_changed && !suspended) => now_playing,
+            _ => return,
+        };
+        let event = NowPlayingEvent {
+            event: PlayerEvent::TrackChanged {
+                track: now_playing.props.clone(),
+            },
+            now_playing: Some(now_playing),
+        };
+        // Local sinks only care about the profile once they resume
+        self.dispatch(&event, floor, |sink| {
+            !sink.bypass_incognito && (resumed || !sink.bypass_privacy)
+        });
+    }
+
+    // Sends `event` to the sinks accepted by `eligible`, as each of them is
+    // allowed to see it: local sinks get it unchanged, broadcasting sinks
+    // after the privacy rules, and sinks left out by a rule's `sinks` target
+    // as if the track were hidden.
+    fn dispatch(
+        &self,
+        event: &NowPlayingEvent,
+        floor: PrivacyAction,
+        eligible: impl Fn(&SinkChannel) -> bool,
+    ) {
+        let filtered = self.privacy.apply(event, floor);
+        let targets = match &event.now_playing {
+            Some(now_playing) if !self.rules.is_empty() => {
+                self.rules.evaluate(now_playing, Utc::now()).actions.sinks
+            }
+            _ => None,
+        };
+        let hidden = targets
+            .as_ref()
+            .and_then(|_| self.privacy.apply(event, PrivacyAction::Hide));
+
+        self.send(|sink| {
+            if !eligible(sink) {
+                None
+            } else if sink.bypass_privacy {
+                Some(event)
+            } else if targets.as_ref().is_some_and(|t| !t.contains(&sink.name)) {
+                hidden.as_ref()
+            } else {
+                filtered.as_ref()
+            }
+        });
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, BusState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn send<'a>(&self, select: impl Fn(&SinkChannel) -> Option<&'a NowPlayingEvent>) {
+        self.senders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|sink| match select(sink) {
+                Some(event) => match sink.tx.send(event.clone()) {
+                    Ok(()) => true,
+                    Err(_) => {
+                        eprintln!("SINK[{}]: worker has stopped, unregistering", sink.name);
+                        false
+                    }
+                },
+                None => true,
+            });
+    }
+}
+
+pub struct SinkBus {
+    publisher: BusPublisher,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl SinkBus {
+    pub fn new(privacy: PrivacyFilter, rules: RuleEngine) -> Self {
+        SinkBus {
+            publisher: BusPublisher {
+                senders: Arc::default(),
+                privacy: Arc::new(privacy),
+                rules: Arc::new(rules),
+                state: Arc::default(),
+            },
+            workers: Vec::new(),
+        }
+    }
+
+    pub fn publisher(&self) -> BusPublisher {
+        self.publisher.clone()
+    }
+
+    pub fn register(
+        &mut self,
+        mut sink: Box<dyn OutputSink>,
+        retry: RetryConfig,
+    ) -> Result<(), AppError> {
+        let name = sink.name().to_string();
+        let bypass_privacy = sink.bypass_privacy();
+        let bypass_incognito = sink.bypass_incognito();
+        let (tx, rx) = mpsc::channel::<NowPlayingEvent>();
+
+        let worker_name = name.clone();
+        let worker = thread::Builder::new()
+            .name(format!("sink-{}", name))
+            .spawn(move || {
+                let name = worker_name;
+                for event in rx {
+                    if let Err(e) = retry_with_policy(&retry, &name, || sink.handle(&event)) {
+                        eprintln!(
+                            "SINK[{}]: dropping {} event: {}",
+                            name,
+                            event.event.name(),
+                            e
+                        );
+                    }
+                }
+                sink.shutdown();
+            })?;
+
+        self.publisher
+            .senders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(SinkChannel {
+                name: name.clone(),
+                bypass_privacy,
+                bypass_incognito,
+                tx,
+            });
+        self.workers.push(worker);
+        println!("SINK[{}]: registered", name);
+        Ok(())
+    }
+
+    // Closes every sink channel and waits for the workers to drain.
+    pub fn shutdown(self) {
+        self.publisher
+            .senders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        for worker in self.workers {
+            let _ = worker.join();
+        }
+    }
+}
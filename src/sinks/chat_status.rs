@@ -0,0 +1,175 @@
+use crate::config::ChatStatusConfig;
+use crate::error::AppError;
+use crate::handlers::chat_status::{
+    mattermost_clear_status, mattermost_get_status, mattermost_set_status, slack_get_status,
+    slack_set_status, SLACK_API_URL,
+};
+use crate::models::{ChatStatus, NowPlaying, NowPlayingEvent, PlaybackState, PlayerEvent};
+use crate::sinks::OutputSink;
+use crate::utils::render_template;
+
+use reqwest::blocking::{Client as HttpClient, ClientBuilder};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Extra time past the end of the track before the status expires on its own,
+// in case the daemon exits without reverting it.
+const EXPIRY_GRACE_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatService {
+    Slack,
+    Mattermost,
+}
+
+// Mirrors the current track into a Slack or Mattermost custom status and
+// restores whatever status was set before once playback stops.
+pub struct ChatStatusSink {
+    service: ChatService,
+    config: ChatStatusConfig,
+    base_url: String,
+    http_client: HttpClient,
+    // `Some` while our status is active, holding the status it replaced
+    previous: Option<Option<ChatStatus>>,
+}
+
+impl ChatStatusSink {
+    pub fn new(service: ChatService, config: ChatStatusConfig) -> Result<Self, AppError> {
+        let base_url = match (&config.base_url, service) {
+            (Some(url), _) => url.trim_end_matches('/').to_string(),
+            (None, ChatService::Slack) => SLACK_API_URL.to_string(),
+            (None, ChatService::Mattermost) => {
+                return Err(AppError::ConfigError(
+                    "mattermost.base_url is required".to_string(),
+                ))
+            }
+        };
+        if config.token.is_empty() {
+            return Err(AppError::ConfigError(format!(
+                "{:?} status sync requires a token",
+                service
+            )));
+        }
+
+        let http_client = ClientBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(ChatStatusSink {
+            service,
+            config,
+            base_url,
+            http_client,
+            previous: None,
+        })
+    }
+
+    fn get_status(&self) -> Result<Option<ChatStatus>, AppError> {
+        match self.service {
+            ChatService::Slack => {
+                slack_get_status(&self.http_client, &self.base_url, &self.config.token).map(
+                    |status| Some(status).filter(|s| !s.text.is_empty() || !s.emoji.is_empty()),
+                )
+            }
+            ChatService::Mattermost => {
+                mattermost_get_status(&self.http_client, &self.base_url, &self.config.token)
+            }
+        }
+    }
+
+    fn set_status(&self, status: &ChatStatus) -> Result<(), AppError> {
+        match self.service {
+            ChatService::Slack => slack_set_status(
+                &self.http_client,
+                &self.base_url,
+                &self.config.token,
+                status,
+            ),
+            ChatService::Mattermost => mattermost_set_status(
+                &self.http_client,
+                &self.base_url,
+                &self.config.token,
+                status,
+            ),
+        }
+    }
+
+    fn clear_status(&self) -> Result<(), AppError> {
+        match self.service {
+            ChatService::Slack => {
+                let empty = ChatStatus {
+                    text: String::new(),
+                    emoji: String::new(),
+                    expires_at: None,
+                };
+                slack_set_status(
+                    &self.http_client,
+                    &self.base_url,
+                    &self.config.token,
+                    &empty,
+                )
+            }
+            ChatService::Mattermost => {
+                mattermost_clear_status(&self.http_client, &self.base_url, &self.config.token)
+            }
+        }
+    }
+
+    fn show_track(&mut self, now_playing: &NowPlaying) -> Result<(), AppError> {
+        // Remember the user's own status the first time we replace it
+        if self.previous.is_none() {
+            self.previous = Some(self.get_status()?);
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| AppError::Other(e.to_string()))?
+            .as_secs() as i64;
+        let remaining = (now_playing.props.duration - now_playing.position()).max(0.0) as i64;
+
+        self.set_status(&ChatStatus {
+            text: render_template(&self.config.template, &now_playing.props),
+            emoji: self.config.emoji.trim_matches(':').to_string(),
+            expires_at: Some(now + remaining + EXPIRY_GRACE_SECS),
+        })
+    }
+
+    fn restore_previous(&mut self) -> Result<(), AppError> {
+        match self.previous.take() {
+            Some(Some(previous)) => self.set_status(&previous),
+            Some(None) => self.clear_status(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl OutputSink for ChatStatusSink {
+    fn name(&self) -> &str {
+        match self.service {
+            ChatService::Slack => "slack",
+            ChatService::Mattermost => "mattermost",
+        }
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        match (&event.event, &event.now_playing) {
+            (PlayerEvent::TrackChanged { .. }, Some(now_playing)) => self.show_track(now_playing),
+            // The expiry follows the track end, so it moves with the position
+            (PlayerEvent::Resumed { .. } | PlayerEvent::Seeked { .. }, Some(now_playing))
+                if now_playing.playback_state == PlaybackState::Playing =>
+            {
+                self.show_track(now_playing)
+            }
+            (PlayerEvent::Stopped, _) => self.restore_previous(),
+            _ => Ok(()),
+        }
+    }
+
+    fn shutdown(&mut self) {
+        if let Err(e) = self.restore_previous() {
+            eprintln!(
+                "SINK[{}]: error restoring previous status: {}",
+                self.name(),
+                e
+            );
+        }
+    }
+}
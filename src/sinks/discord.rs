@@ -0,0 +1,80 @@
+use crate::config::DiscordConfig;
+use crate::error::AppError;
+use crate::handlers::{discord_clear_presence, discord_update_presence};
+use crate::models::{NowPlaying, NowPlayingEvent, PlaybackState, PlayerEvent};
+use crate::sinks::{BusPublisher, OutputSink};
+
+use chrono::Utc;
+use discord_presence::Client;
+
+const DISCORD_APP_ID: u64 = 773825528921849856;
+
+pub struct DiscordSink {
+    client: Client,
+    config: DiscordConfig,
+    // Consulted for the active schedule profile and presence rules
+    publisher: BusPublisher,
+}
+
+impl DiscordSink {
+    pub fn new(config: DiscordConfig, publisher: BusPublisher) -> Self {
+        let mut client = Client::new(DISCORD_APP_ID);
+        let ready_publisher = publisher.clone();
+        client
+            .on_ready(move |_ctx| {
+                println!("Discord RPC connected!");
+                ready_publisher.publish(PlayerEvent::DiscordConnected, None);
+            })
+            .persist();
+        client.start();
+
+        DiscordSink {
+            client,
+            config,
+            publisher,
+        }
+    }
+
+    // Layers the active profile and then any matching rules over the config
+    fn update_presence(&mut self, now_playing: &NowPlaying) -> Result<(), AppError> {
+        let config = match self.publisher.profile() {
+            Some(active) => active.profile.discord_config(&self.config),
+            None => self.config.clone(),
+        };
+        let outcome = self.publisher.rules().evaluate(now_playing, Utc::now());
+        let config = outcome.actions.discord_config(&config);
+        discord_update_presence(&mut self.client, &config, now_playing)
+    }
+}
+
+impl OutputSink for DiscordSink {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        match (&event.event, &event.now_playing) {
+            (PlayerEvent::TrackChanged { .. }, Some(now_playing)) => {
+                self.update_presence(now_playing)
+            }
+            // Timestamps need refreshing whenever the position jumps
+            (PlayerEvent::Resumed { .. } | PlayerEvent::Seeked { .. }, Some(now_playing))
+                if now_playing.playback_state == PlaybackState::Playing =>
+            {
+                self.update_presence(now_playing)
+            }
+            (PlayerEvent::Stopped, _) => {
+                println!("DEBUG: No song playing");
+                discord_clear_presence(&mut self.client)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn shutdown(&mut self) {
+        if let Err(e) = discord_clear_presence(&mut self.client) {
+            eprintln!("DEBUG: error in clear_activity: {}", e);
+        }
+        println!("Disconnected from Discord RPC.");
+    }
+}
@@ -0,0 +1,47 @@
+use crate::config::FileOutputConfig;
+use crate::error::AppError;
+use crate::handlers::{clear_now_playing_files, write_now_playing_files};
+use crate::models::{NowPlayingEvent, PlayerEvent};
+use crate::sinks::OutputSink;
+
+use reqwest::blocking::{Client as HttpClient, ClientBuilder};
+use std::time::Duration;
+
+pub struct FileSink {
+    config: FileOutputConfig,
+    http_client: HttpClient,
+}
+
+impl FileSink {
+    pub fn new(config: FileOutputConfig) -> Result<Self, AppError> {
+        let http_client = ClientBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(FileSink {
+            config,
+            http_client,
+        })
+    }
+}
+
+impl OutputSink for FileSink {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        match (&event.event, &event.now_playing) {
+            (PlayerEvent::TrackChanged { .. }, Some(now_playing)) => {
+                write_now_playing_files(&self.config, &self.http_client, now_playing)
+            }
+            (PlayerEvent::Stopped, _) => clear_now_playing_files(&self.config),
+            _ => Ok(()),
+        }
+    }
+
+    fn shutdown(&mut self) {
+        if let Err(e) = clear_now_playing_files(&self.config) {
+            eprintln!("FILE_OUTPUT: error in clear_now_playing_files: {}", e);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::error::AppError;
+use crate::handlers::{append_play, PlayTracker};
+use crate::models::{NowPlayingEvent, PlaybackState, PlayerEvent};
+use crate::sinks::OutputSink;
+
+// Records finished plays to the local listening history.
+pub struct HistorySink {
+    current_play: Option<PlayTracker>,
+    record_incognito: bool,
+}
+
+impl HistorySink {
+    pub fn new(record_incognito: bool) -> Self {
+        HistorySink {
+            current_play: None,
+            record_incognito,
+        }
+    }
+
+    fn finish_current_play(&mut self) -> Result<(), AppError> {
+        match self.current_play.take() {
+            Some(tracker) => append_play(&tracker.finish()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl OutputSink for HistorySink {
+    fn name(&self) -> &str {
+        "history"
+    }
+
+    fn bypass_privacy(&self) -> bool {
+        true
+    }
+
+    fn bypass_incognito(&self) -> bool {
+        self.record_incognito
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        match (&event.event, &event.now_playing) {
+            (PlayerEvent::TrackChanged { .. }, Some(now_playing)) => {
+                // Close out the previous play and start tracking the new one
+                let previous = self.current_play.take();
+                let mut tracker = PlayTracker::start(now_playing.props.clone());
+                if now_playing.playback_state != PlaybackState::Playing {
+                    tracker.pause();
+                }
+                self.current_play = Some(tracker);
+
+                match previous {
+                    Some(previous) => append_play(&previous.finish()),
+                    None => Ok(()),
+                }
+            }
+            (PlayerEvent::Paused { .. }, _) => {
+                if let Some(tracker) = self.current_play.as_mut() {
+                    tracker.pause();
+                }
+                Ok(())
+            }
+            (PlayerEvent::Resumed { .. }, _) => {
+                if let Some(tracker) = self.current_play.as_mut() {
+                    tracker.resume();
+                }
+                Ok(())
+            }
+            (PlayerEvent::Stopped, _) => self.finish_current_play(),
+            _ => Ok(()),
+        }
+    }
+
+    fn shutdown(&mut self) {
+        if let Err(e) = self.finish_current_play() {
+            eprintln!("HISTORY: error in append_play: {}", e);
+        }
+    }
+}
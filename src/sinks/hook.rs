@@ -0,0 +1,78 @@
+use crate::config::HookConfig;
+use crate::error::AppError;
+use crate::handlers::hook::{hook_env, hook_payload, run_hook};
+use crate::models::NowPlayingEvent;
+use crate::sinks::OutputSink;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+// One sink per configured hook. Commands run on their own threads so a slow
+// one never delays the next event; at most `max_concurrent` run at once.
+pub struct HookSink {
+    config: Arc<HookConfig>,
+    name: String,
+    running: Arc<AtomicUsize>,
+}
+
+impl HookSink {
+    pub fn new(config: HookConfig) -> Result<Self, AppError> {
+        if config.command.trim().is_empty() {
+            return Err(AppError::ConfigError(format!(
+                "hook '{}' has no command",
+                config.name
+            )));
+        }
+        if config.max_concurrent == 0 {
+            return Err(AppError::ConfigError(format!(
+                "hook '{}': max_concurrent must be at least 1",
+                config.name
+            )));
+        }
+        Ok(HookSink {
+            name: format!("hook:{}", config.name),
+            config: Arc::new(config),
+            running: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+}
+
+impl OutputSink for HookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        let event_name = event.event.name();
+        if !self.config.events.iter().any(|e| e == event_name) {
+            return Ok(());
+        }
+
+        let limit = self.config.max_concurrent;
+        let reserved = self
+            .running
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                (running < limit).then_some(running + 1)
+            });
+        if reserved.is_err() {
+            eprintln!(
+                "HOOK {}: {} command(s) still running, skipping {}",
+                self.config.name, limit, event_name
+            );
+            return Ok(());
+        }
+
+        let config = self.config.clone();
+        let running = self.running.clone();
+        let env = hook_env(event);
+        let payload = hook_payload(event);
+        thread::spawn(move || {
+            if let Err(e) = run_hook(&config, &env, &payload) {
+                eprintln!("HOOK {}: {}", config.name, e);
+            }
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::config::{data_dir, MastodonConfig, MastodonPostMode};
+use crate::error::AppError;
+use crate::handlers::history::{load_plays, local_midnight_utc};
+use crate::handlers::mastodon::{
+    mastodon_post_status, mastodon_upload_media, render_daily_summary, MASTODON_VISIBILITIES,
+};
+use crate::handlers::stats::build_stats_report;
+use crate::models::{NowPlaying, NowPlayingEvent, PlayerEvent};
+use crate::sinks::OutputSink;
+use crate::utils::render_template;
+
+use chrono::{Days, Local, NaiveDate};
+use reqwest::blocking::{Client as HttpClient, ClientBuilder};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const SUMMARY_TOP_LIMIT: usize = 3;
+
+// Posts a templated status for tracks selected by `post`, with the artwork
+// attached. Posts closer together than `min_interval_secs` are dropped.
+pub struct MastodonSink {
+    config: MastodonConfig,
+    base_url: String,
+    http_client: HttpClient,
+    last_post_at: Option<Instant>,
+    last_album: Option<(String, String)>,
+    // Persisted so restarts don't post the same summary twice
+    summary_state_path: PathBuf,
+}
+
+impl MastodonSink {
+    pub fn new(config: MastodonConfig) -> Result<Self, AppError> {
+        if config.base_url.is_empty() || config.access_token.is_empty() {
+            return Err(AppError::ConfigError(
+                "mastodon requires base_url and access_token".to_string(),
+            ));
+        }
+        if !MASTODON_VISIBILITIES.contains(&config.visibility.as_str()) {
+            return Err(AppError::ConfigError(format!(
+                "invalid mastodon visibility '{}', expected one of {}",
+                config.visibility,
+                MASTODON_VISIBILITIES.join(", ")
+            )));
+        }
+
+        let http_client = ClientBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        Ok(MastodonSink {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            config,
+            http_client,
+            last_post_at: None,
+            last_album: None,
+            summary_state_path: data_dir().join("mastodon-last-summary"),
+        })
+    }
+
+    fn rate_limited(&self) -> bool {
+        self.last_post_at
+            .is_some_and(|at| at.elapsed() < Duration::from_secs(self.config.min_interval_secs))
+    }
+
+    fn post(&mut self, text: &str, media_ids: &[String], key: &str) -> Result<(), AppError> {
+        let status = mastodon_post_status(
+            &self.http_client,
+            &self.base_url,
+            &self.config.access_token,
+            text,
+            &self.config.visibility,
+            media_ids,
+            key,
+        )?;
+        self.last_post_at = Some(Instant::now());
+        println!(
+            "MASTODON: posted status {}",
+            status.url.unwrap_or(status.id)
+        );
+        Ok(())
+    }
+
+    fn post_track(&mut self, now_playing: &NowPlaying) -> Result<(), AppError> {
+        let props = &now_playing.props;
+        let album = (props.artist.clone(), props.album.clone());
+        if self.config.post == MastodonPostMode::FirstTrackOfAlbum
+            && (props.album.is_empty() || self.last_album.as_ref() == Some(&album))
+        {
+            return Ok(());
+        }
+        if self.rate_limited() {
+            println!("MASTODON: skipping post for '{}', rate limited", props.name);
+            self.last_album = Some(album);
+            return Ok(());
+        }
+
+        let text = render_template(&self.config.template, props);
+        let mut media_ids = Vec::new();
+        if let (true, Some(url)) = (self.config.attach_artwork, &now_playing.artwork_url) {
+            let image = self.http_client.get(url).send()?.error_for_status()?;
+            let description = format!("Album artwork for {} by {}", props.album, props.artist);
+            media_ids.push(mastodon_upload_media(
+                &self.http_client,
+                &self.base_url,
+                &self.config.access_token,
+                image.bytes()?.to_vec(),
+                &description,
+            )?);
+        }
+
+        // Stable across retries of the same event, so duplicates are dropped
+        let key = Sha256::new()
+            .chain_update(&text)
+            .chain_update(format!("{:?}", now_playing.position_updated_at))
+            .finalize();
+        self.post(&text, &media_ids, &hex::encode(key))?;
+        // Only after a successful post, so a retried event isn't skipped
+        self.last_album = Some(album);
+        Ok(())
+    }
+
+    fn post_daily_summary(&mut self) -> Result<(), AppError> {
+        let today = Local::now().date_naive();
+        let last_summary = fs::read_to_string(&self.summary_state_path)
+            .ok()
+            .and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok());
+        let yesterday = today - Days::new(1);
+        if last_summary.is_some_and(|date| date >= yesterday) {
+            return Ok(());
+        }
+
+        let plays = load_plays(local_midnight_utc(yesterday)?, local_midnight_utc(today)?)?;
+        if !plays.is_empty() {
+            let report = build_stats_report(&plays, yesterday, yesterday, SUMMARY_TOP_LIMIT);
+            let text = render_daily_summary(&self.config.summary_template, yesterday, &report);
+            self.post(&text, &[], &format!("daily-summary-{}", yesterday))?;
+        }
+
+        if let Some(parent) = self.summary_state_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.summary_state_path, yesterday.to_string())?;
+        Ok(())
+    }
+}
+
+impl OutputSink for MastodonSink {
+    fn name(&self) -> &str {
+        "mastodon"
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        match (&event.event, &event.now_playing, self.config.post) {
+            // The summary goes out with the first track of a new day
+            (PlayerEvent::TrackChanged { .. }, _, MastodonPostMode::DailySummary) => {
+                self.post_daily_summary()
+            }
+            (PlayerEvent::TrackChanged { .. }, Some(now_playing), _) => {
+                self.post_track(now_playing)
+            }
+            _ => Ok(()),
+        }
+    }
+}
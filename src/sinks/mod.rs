@@ -0,0 +1,102 @@
+pub mod api;
+pub mod bus;
+pub mod chat_status;
+pub mod discord;
+pub mod file;
+pub mod history;
+pub mod hook;
+pub mod mastodon;
+pub mod mqtt;
+pub mod webhook;
+
+// Re-exports for convenient access
+pub use api::ApiSink;
+pub use bus::{BusPublisher, SinkBus};
+pub use chat_status::{ChatService, ChatStatusSink};
+pub use discord::DiscordSink;
+pub use file::FileSink;
+pub use history::HistorySink;
+pub use hook::HookSink;
+pub use mastodon::MastodonSink;
+pub use mqtt::MqttSink;
+pub use webhook::WebhookSink;
+
+use crate::config::{Config, RetryConfig};
+use crate::error::AppError;
+use crate::events::SharedEventHub;
+use crate::models::{NowPlayingEvent, SharedPlayerState};
+
+// A consumer of now-playing events. Each registered sink runs on its own
+// thread, so a slow or failing sink never blocks the others.
+pub trait OutputSink: Send {
+    fn name(&self) -> &str;
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError>;
+
+    // Local-only sinks receive tracks hidden by the privacy rules unchanged
+    fn bypass_privacy(&self) -> bool {
+        false
+    }
+
+    // Sinks that keep receiving events while incognito mode is on
+    fn bypass_incognito(&self) -> bool {
+        false
+    }
+
+    // Called once when the bus shuts down, after the last event
+    fn shutdown(&mut self) {}
+}
+
+pub fn register_sinks(
+    bus: &mut SinkBus,
+    config: &Config,
+    state: SharedPlayerState,
+    events: SharedEventHub,
+) -> Result<(), AppError> {
+    if config.api.enabled {
+        bus.register(
+            Box::new(ApiSink::new(state, events)),
+            RetryConfig::disabled(),
+        )?;
+    }
+    if config.discord.enabled {
+        let sink = DiscordSink::new(config.discord.clone(), bus.publisher());
+        bus.register(Box::new(sink), config.discord.retry.clone())?;
+    }
+    if config.file_output.enabled {
+        let sink = FileSink::new(config.file_output.clone())?;
+        bus.register(Box::new(sink), config.file_output.retry.clone())?;
+    }
+    if config.mqtt.enabled {
+        let sink = MqttSink::new(config.mqtt.clone())?;
+        bus.register(Box::new(sink), config.mqtt.retry.clone())?;
+    }
+    if config.slack.enabled {
+        let sink = ChatStatusSink::new(ChatService::Slack, config.slack.clone())?;
+        bus.register(Box::new(sink), config.slack.retry.clone())?;
+    }
+    if config.mattermost.enabled {
+        let sink = ChatStatusSink::new(ChatService::Mattermost, config.mattermost.clone())?;
+        bus.register(Box::new(sink), config.mattermost.retry.clone())?;
+    }
+    if config.mastodon.enabled {
+        let sink = MastodonSink::new(config.mastodon.clone())?;
+        bus.register(Box::new(sink), config.mastodon.retry.clone())?;
+    }
+    for webhook in &config.webhooks {
+        // Webhooks retry internally and dead-letter on exhaustion
+        let sink = WebhookSink::new(webhook.clone())?;
+        bus.register(Box::new(sink), RetryConfig::disabled())?;
+    }
+    for hook in &config.hooks {
+        // A failed command is logged, not retried, as it may have side effects
+        let sink = HookSink::new(hook.clone())?;
+        bus.register(Box::new(sink), RetryConfig::disabled())?;
+    }
+    if config.history.enabled {
+        // Tracker state is consumed while handling, so history is never retried
+        let sink = HistorySink::new(config.history.record_incognito);
+        bus.register(Box::new(sink), RetryConfig::disabled())?;
+    }
+    Ok(())
+}
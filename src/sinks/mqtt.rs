@@ -0,0 +1,157 @@
+use crate::config::MqttConfig;
+use crate::error::AppError;
+use crate::handlers::mqtt::{
+    mqtt_attributes_payload, mqtt_attributes_topic, mqtt_availability_topic,
+    mqtt_discovery_messages, mqtt_state_payload, mqtt_state_topic, AVAILABILITY_OFFLINE,
+    AVAILABILITY_ONLINE,
+};
+use crate::models::{NowPlaying, NowPlayingEvent, PlayerEvent};
+use crate::sinks::OutputSink;
+
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+pub struct MqttSink {
+    client: Client,
+    config: MqttConfig,
+    // Last published track, replayed after the broker connection is re-established
+    last_now_playing: Arc<Mutex<Option<NowPlaying>>>,
+}
+
+impl MqttSink {
+    pub fn new(config: MqttConfig) -> Result<Self, AppError> {
+        let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        options.set_last_will(LastWill::new(
+            mqtt_availability_topic(&config),
+            AVAILABILITY_OFFLINE,
+            QoS::AtLeastOnce,
+            true,
+        ));
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+
+        let (client, mut connection) = Client::new(options, 16);
+        let last_now_playing = Arc::new(Mutex::new(None));
+
+        // The connection must be polled continuously to make progress; it
+        // reconnects by itself on the next poll after an error.
+        let reconnect_client = client.clone();
+        let reconnect_config = config.clone();
+        let reconnect_state = Arc::clone(&last_now_playing);
+        thread::Builder::new()
+            .name("mqtt-connection".to_string())
+            .spawn(move || {
+                for notification in connection.iter() {
+                    match notification {
+                        Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                            println!(
+                                "MQTT: connected to {}:{}",
+                                reconnect_config.host, reconnect_config.port
+                            );
+                            let now_playing = reconnect_state
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .clone();
+                            if let Err(e) = publish_connected(
+                                &reconnect_client,
+                                &reconnect_config,
+                                now_playing.as_ref(),
+                            ) {
+                                eprintln!("MQTT: error in publish_connected: {}", e);
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            eprintln!("MQTT: connection error: {}", e);
+                            thread::sleep(RECONNECT_DELAY);
+                        }
+                    }
+                }
+            })?;
+
+        Ok(MqttSink {
+            client,
+            config,
+            last_now_playing,
+        })
+    }
+}
+
+fn publish_connected(
+    client: &Client,
+    config: &MqttConfig,
+    now_playing: Option<&NowPlaying>,
+) -> Result<(), AppError> {
+    client.publish(
+        mqtt_availability_topic(config),
+        QoS::AtLeastOnce,
+        true,
+        AVAILABILITY_ONLINE,
+    )?;
+    if config.discovery {
+        for (topic, payload) in mqtt_discovery_messages(config) {
+            client.publish(topic, QoS::AtLeastOnce, true, payload.to_string())?;
+        }
+    }
+    publish_now_playing(client, config, now_playing)
+}
+
+fn publish_now_playing(
+    client: &Client,
+    config: &MqttConfig,
+    now_playing: Option<&NowPlaying>,
+) -> Result<(), AppError> {
+    client.publish(
+        mqtt_state_topic(config),
+        QoS::AtLeastOnce,
+        true,
+        mqtt_state_payload(now_playing),
+    )?;
+    client.publish(
+        mqtt_attributes_topic(config),
+        QoS::AtLeastOnce,
+        true,
+        mqtt_attributes_payload(now_playing).to_string(),
+    )?;
+    Ok(())
+}
+
+impl OutputSink for MqttSink {
+    fn name(&self) -> &str {
+        "mqtt"
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        if matches!(event.event, PlayerEvent::DiscordConnected) {
+            return Ok(());
+        }
+
+        *self
+            .last_now_playing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = event.now_playing.clone();
+        publish_now_playing(&self.client, &self.config, event.now_playing.as_ref())
+    }
+
+    fn shutdown(&mut self) {
+        let result = publish_now_playing(&self.client, &self.config, None).and_then(|_| {
+            self.client.publish(
+                mqtt_availability_topic(&self.config),
+                QoS::AtLeastOnce,
+                true,
+                AVAILABILITY_OFFLINE,
+            )?;
+            self.client.disconnect()?;
+            Ok(())
+        });
+        if let Err(e) = result {
+            eprintln!("MQTT: error during shutdown: {}", e);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::config::WebhookConfig;
+use crate::error::AppError;
+use crate::handlers::webhook::{append_dead_letter, build_webhook_body, send_webhook};
+use crate::models::NowPlayingEvent;
+use crate::sinks::OutputSink;
+use crate::utils::retry_with_policy;
+
+use reqwest::blocking::{Client as HttpClient, ClientBuilder};
+use std::time::Duration;
+
+// One sink per configured webhook. Retries happen here rather than on the bus
+// so that exhausted deliveries can be written to the dead-letter log.
+pub struct WebhookSink {
+    config: WebhookConfig,
+    http_client: HttpClient,
+    name: String,
+}
+
+impl WebhookSink {
+    pub fn new(config: WebhookConfig) -> Result<Self, AppError> {
+        if config.url.is_empty() {
+            return Err(AppError::ConfigError(format!(
+                "webhook '{}' has no url",
+                config.name
+            )));
+        }
+        let http_client = ClientBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(WebhookSink {
+            name: format!("webhook:{}", config.name),
+            config,
+            http_client,
+        })
+    }
+}
+
+impl OutputSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn handle(&mut self, event: &NowPlayingEvent) -> Result<(), AppError> {
+        let event_name = event.event.name();
+        if !self.config.events.iter().any(|e| e == event_name) {
+            return Ok(());
+        }
+
+        let body = match build_webhook_body(&self.config, event) {
+            Ok(body) => body,
+            Err(e) => {
+                append_dead_letter(&self.config, event_name, "", &e)?;
+                return Err(e);
+            }
+        };
+
+        let result = retry_with_policy(&self.config.retry, &self.name, || {
+            send_webhook(&self.http_client, &self.config, event_name, &body)
+        });
+        if let Err(e) = &result {
+            append_dead_letter(&self.config, event_name, &body, e)?;
+        }
+        result
+    }
+}
@@ -1,3 +1,9 @@
+pub mod retry;
 pub mod string;
+pub mod template;
 
-pub use string::{lucene_escape, remove_parentheses_content, truncate_string};
+pub use retry::retry_with_policy;
+pub use string::{
+    glob_to_regex, lucene_escape, parse_duration, remove_parentheses_content, truncate_string,
+};
+pub use template::{format_timestamp, render_json_template, render_template};
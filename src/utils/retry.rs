@@ -0,0 +1,42 @@
+use crate::config::RetryConfig;
+use crate::error::AppError;
+
+use backoff::ExponentialBackoff;
+use std::time::Duration;
+
+// Runs `op` under the configured backoff policy, retrying transient errors.
+pub fn retry_with_policy<T, F>(policy: &RetryConfig, label: &str, mut op: F) -> Result<T, AppError>
+where
+    F: FnMut() -> Result<T, AppError>,
+{
+    if policy.max_elapsed_secs == 0 {
+        return op();
+    }
+
+    let backoff = ExponentialBackoff {
+        initial_interval: Duration::from_millis(policy.initial_interval_ms),
+        max_interval: Duration::from_millis(policy.max_interval_ms),
+        max_elapsed_time: Some(Duration::from_secs(policy.max_elapsed_secs)),
+        ..ExponentialBackoff::default()
+    };
+
+    backoff::retry_notify(
+        backoff,
+        || {
+            op().map_err(|e| {
+                if e.is_transient() {
+                    backoff::Error::transient(e)
+                } else {
+                    backoff::Error::permanent(e)
+                }
+            })
+        },
+        |e: AppError, wait: Duration| {
+            eprintln!("RETRY[{}]: {} (retrying in {:?})", label, e, wait);
+        },
+    )
+    .map_err(|e| match e {
+        backoff::Error::Permanent(err) => err,
+        backoff::Error::Transient { err, .. } => err,
+    })
+}
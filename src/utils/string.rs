@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 pub fn lucene_escape(term: &str) -> String {
     let special_chars = [
         '+', '-', '&', '|', '!', '(', ')', '{', '}', '[', ']', '^', '"', '~', '*', '?', ':', '\\',
@@ -12,6 +14,21 @@ pub fn lucene_escape(term: &str) -> String {
     result
 }
 
+// Translates a `*`/`?` glob into an anchored regex pattern
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::with_capacity(glob.len() * 2 + 2);
+    pattern.push('^');
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
 pub fn remove_parentheses_content(term: &str) -> String {
     term.chars()
         .scan(0, |depth, c| {
@@ -40,3 +57,17 @@ pub fn truncate_string(value: &str) -> String {
         truncated
     }
 }
+
+// Parses durations like "90s", "30m" or "2h"; a bare number means minutes.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit_secs) = match value.char_indices().last()? {
+        (i, 's') => (&value[..i], 1),
+        (i, 'm') => (&value[..i], 60),
+        (i, 'h') => (&value[..i], 3600),
+        (i, 'd') => (&value[..i], 86_400),
+        _ => (value, 60),
+    };
+    let number: u64 = number.trim().parse().ok()?;
+    Some(Duration::from_secs(number.checked_mul(unit_secs)?))
+}
@@ -0,0 +1,82 @@
+use crate::models::MusicProps;
+
+// Renders `{field}` placeholders from the track properties. Unknown
+// placeholders are kept verbatim and `{{` / `}}` produce literal braces.
+pub fn render_template(template: &str, props: &MusicProps) -> String {
+    render_with(template, props, |value| value.to_string())
+}
+
+// Like `render_template`, but escapes values for use inside JSON strings,
+// e.g. `{"text": "{artist} - {name}"}`.
+pub fn render_json_template(template: &str, props: &MusicProps) -> String {
+    render_with(template, props, |value| {
+        let quoted = serde_json::Value::from(value).to_string();
+        quoted[1..quoted.len() - 1].to_string()
+    })
+}
+
+fn render_with<F>(template: &str, props: &MusicProps, escape: F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut key = String::new();
+                let mut closed = false;
+                for k in chars.by_ref() {
+                    if k == '}' {
+                        closed = true;
+                        break;
+                    }
+                    key.push(k);
+                }
+                match template_value(key.trim(), props) {
+                    Some(value) if closed => output.push_str(&escape(&value)),
+                    _ => {
+                        output.push('{');
+                        output.push_str(&key);
+                        if closed {
+                            output.push('}');
+                        }
+                    }
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn template_value(key: &str, props: &MusicProps) -> Option<String> {
+    let value = match key {
+        "name" | "title" => props.name.clone(),
+        "artist" => props.artist.clone(),
+        "album" => props.album.clone(),
+        "duration" => format_timestamp(props.duration),
+        "position" => format_timestamp(props.player_position),
+        _ => return None,
+    };
+    Some(value)
+}
+
+pub fn format_timestamp(secs: f64) -> String {
+    let total = if secs.is_finite() {
+        secs.max(0.0) as u64
+    } else {
+        0
+    };
+    format!("{}:{:02}", total / 60, total % 60)
+}